@@ -0,0 +1,39 @@
+// グリッドの線とマスの塗り (src/systems/grid.rs の GridMaterial)
+// 座標はカメラの近くのマス (origin) からのマス数。マスの境目が整数になる
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+const MAX_GRID_HIGHLIGHTS: u32 = 16u;
+
+struct GridParams {
+    color: vec4<f32>,
+    center: vec2<f32>,
+    size: vec2<f32>,
+    step: f32,
+    highlight_count: u32,
+    highlights: array<vec4<f32>, MAX_GRID_HIGHLIGHTS>,
+    highlight_colors: array<vec4<f32>, MAX_GRID_HIGHLIGHTS>,
+}
+
+@group(2) @binding(0) var<uniform> grid: GridParams;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // uv の y は下向き
+    let p = grid.center + vec2<f32>(mesh.uv.x - 0.5, 0.5 - mesh.uv.y) * grid.size;
+
+    // 塗るマス
+    var base = vec4<f32>(grid.color.rgb, 0.0);
+    let cell = floor(p);
+    for (var i = 0u; i < grid.highlight_count; i = i + 1u) {
+        if all(cell == grid.highlights[i].xy) {
+            base = grid.highlight_colors[i];
+        }
+    }
+
+    // 一番近い線までの距離 (画面のピクセル)。2 ピクセルほどの太さにする
+    let q = p / grid.step;
+    let d = abs(q - round(q)) / max(fwidth(q), vec2<f32>(1e-6));
+    let line = 1.0 - smoothstep(0.5, 1.5, min(d.x, d.y));
+    return mix(base, grid.color, line);
+}
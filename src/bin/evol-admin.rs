@@ -0,0 +1,347 @@
+use evolution_game::database::{self, UserSummary};
+use evolution_game::map::{is_bot_spawn, is_obstacle};
+use evolution_game::map_export::{self, Region, Sample};
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+use postgres::NoTls;
+use serde_json::{json, Value};
+use std::process::exit;
+
+// ==========================================
+// 【新規】管理用 CLI
+// cargo run --no-default-features --bin evol-admin -- [--json] <command> ...
+// DB は DB_HOST で指定する（world コマンドは DB を使わない）
+// ==========================================
+
+const USAGE: &str = "\
+Usage: evol-admin [--json] <command>
+
+  users list [--limit N]
+  users search <pattern> [--limit N]
+  users show <name>
+  users create <name> <password>
+  users delete <name>
+  users passwd <name> <password>
+  users teleport <name> <x> <y> [--force]
+  users grant-word <name> <word>
+  users grant-emoji <name> <emoji>
+  stats
+  leaderboard [--limit N]
+  world obstacle <x> <y>
+  world bots <x0> <y0> <x1> <y1>
+  world ascii <x0> <y0> <x1> <y1> [--step N]
+  world png <x0> <y0> <x1> <y1> <file.png> [--step N] [--scale N]
+  world density <x0> <y0> <x1> <y1> [--step N]
+
+  --step N   sample every N-th cell (for huge regions)
+  --scale N  pixels per sampled cell in the PNG (default 2)";
+
+const DEFAULT_LIMIT: i64 = 50;
+
+// world コマンドで調べるマスの上限（うっかり巨大な範囲を指定しないように。--step で間引いた後の数）
+const MAX_REGION_CELLS: i64 = 1_000_000;
+
+type DbPool = Pool<PostgresConnectionManager<NoTls>>;
+
+struct Args {
+    json: bool,
+    force: bool,
+    limit: i64,
+    step: i64,
+    scale: u32,
+    words: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { json: false, force: false, limit: DEFAULT_LIMIT, step: 1, scale: 2, words: Vec::new() };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => args.json = true,
+            "--force" => args.force = true,
+            "--limit" => {
+                let value = iter.next().ok_or("--limit needs a number")?;
+                args.limit = value.parse().map_err(|_| format!("bad --limit: {}", value))?;
+            }
+            "--step" => {
+                let value = iter.next().ok_or("--step needs a number")?;
+                args.step = value.parse().ok().filter(|&n: &i64| n > 0).ok_or(format!("bad --step: {}", value))?;
+            }
+            "--scale" => {
+                let value = iter.next().ok_or("--scale needs a number")?;
+                args.scale = value.parse().ok().filter(|&n: &u32| (1..=64).contains(&n)).ok_or(format!("bad --scale: {}", value))?;
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => args.words.push(arg),
+        }
+    }
+    Ok(args)
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(output) => print(&args, output),
+        Err(e) if e == USAGE => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+        Err(e) => {
+            if args.json {
+                println!("{}", json!({ "error": e }));
+            } else {
+                eprintln!("Error: {}", e);
+            }
+            exit(1);
+        }
+    }
+}
+
+// コマンドの結果（JSON と人間向けの両方の形）
+struct Output {
+    json: Value,
+    text: String,
+}
+
+fn print(args: &Args, output: Output) {
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&output.json).unwrap_or_default());
+    } else {
+        print!("{}", output.text);
+        if !output.text.ends_with('\n') {
+            println!();
+        }
+    }
+}
+
+fn run(args: &Args) -> Result<Output, String> {
+    let words: Vec<&str> = args.words.iter().map(String::as_str).collect();
+    match words.as_slice() {
+        ["users", "list"] => list_users(&pool(), None, args.limit),
+        ["users", "search", pattern] => list_users(&pool(), Some(pattern), args.limit),
+        ["users", "show", name] => show_user(&pool(), name),
+        ["users", "create", name, password] => {
+            if !database::is_valid_credential(name) || !database::is_valid_credential(password) {
+                return Err("name and password must be 1-12 ASCII letters or digits".to_string());
+            }
+            let pool = pool();
+            if database::user_exists(&pool, name)? {
+                return Err(format!("user {} already exists", name));
+            }
+            database::create_user(&pool, name, password)?;
+            Ok(done(format!("Created {}", name)))
+        }
+        ["users", "delete", name] => require(database::delete_user(&pool(), name)?, name, format!("Deleted {}", name)),
+        ["users", "passwd", name, password] => {
+            if !database::is_valid_credential(password) {
+                return Err("password must be 1-12 ASCII letters or digits".to_string());
+            }
+            require(database::set_password(&pool(), name, password)?, name, format!("Password reset for {}", name))
+        }
+        ["users", "teleport", name, x, y] => {
+            let (x, y) = (parse_coord(x)?, parse_coord(y)?);
+            if is_obstacle(x, y) && !args.force {
+                return Err(format!("({}, {}) is an obstacle (use --force to teleport anyway)", x, y));
+            }
+            require(database::teleport_user(&pool(), name, x, y)?, name, format!("Teleported {} to ({}, {})", name, x, y))
+        }
+        ["users", "grant-word", name, word] => {
+            let pool = pool();
+            if !database::user_exists(&pool, name)? {
+                return Err(format!("no such user: {}", name));
+            }
+            let added = database::grant_word(&pool, name, word)?;
+            Ok(done(if added { format!("Gave \"{}\" to {}", word, name) } else { format!("{} already knows \"{}\"", name, word) }))
+        }
+        ["users", "grant-emoji", name, emoji] => {
+            let pool = pool();
+            if !database::user_exists(&pool, name)? {
+                return Err(format!("no such user: {}", name));
+            }
+            let added = database::grant_emoji(&pool, name, emoji)?;
+            Ok(done(if added { format!("Gave {} to {}", emoji, name) } else { format!("{} already has {}", name, emoji) }))
+        }
+        ["stats"] => stats(&pool()),
+        ["leaderboard"] => leaderboard(&pool(), args.limit),
+        ["world", "obstacle", x, y] => {
+            let (x, y) = (parse_coord(x)?, parse_coord(y)?);
+            let obstacle = is_obstacle(x, y);
+            Ok(Output {
+                json: json!({ "x": x, "y": y, "obstacle": obstacle }),
+                text: format!("({}, {}) is {}", x, y, if obstacle { "an obstacle" } else { "free" }),
+            })
+        }
+        ["world", "bots", x0, y0, x1, y1] => {
+            let r = parse_region(x0, y0, x1, y1, 1)?;
+            let bots: Vec<(i64, i64)> = (r.y0..=r.y1)
+                .flat_map(|y| (r.x0..=r.x1).map(move |x| (x, y)))
+                .filter(|&(x, y)| is_bot_spawn(x, y))
+                .collect();
+            let text = bots.iter().map(|(x, y)| format!("({}, {})\n", x, y)).collect::<String>()
+                + &format!("{} bots", bots.len());
+            Ok(Output {
+                json: json!(bots.iter().map(|&(x, y)| json!({ "x": x, "y": y })).collect::<Vec<_>>()),
+                text,
+            })
+        }
+        ["world", "ascii", x0, y0, x1, y1] => {
+            let sample = map_export::sample(parse_region(x0, y0, x1, y1, args.step)?);
+            let width = sample.region.width() as usize;
+            let rows: Vec<String> = sample.tiles.chunks(width).map(|row| row.iter().map(|t| t.ascii()).collect()).collect();
+            let mut json = density_json(&sample);
+            json["rows"] = json!(rows);
+            Ok(Output { json, text: sample.to_ascii() })
+        }
+        ["world", "png", x0, y0, x1, y1, file] => {
+            let sample = map_export::sample(parse_region(x0, y0, x1, y1, args.step)?);
+            sample.write_png(file, args.scale)?;
+            let mut json = density_json(&sample);
+            json["file"] = json!(file);
+            Ok(Output { json, text: format!("Wrote {}\n{}", file, sample.legend().join("\n")) })
+        }
+        ["world", "density", x0, y0, x1, y1] => {
+            let sample = map_export::sample(parse_region(x0, y0, x1, y1, args.step)?);
+            let mut text = format!("{} cells sampled (step {})\n", sample.tiles.len(), sample.region.step);
+            text += &format!("{:<9} {:>10} {:>9} {:>9} {:>10}\n", "TILE", "COUNT", "ACTUAL", "EXPECTED", "CLUSTERING");
+            for s in sample.stats() {
+                let clustering = s.clustering.map(|c| format!("{:.2}", c)).unwrap_or_else(|| "-".to_string());
+                text += &format!(
+                    "{:<9} {:>10} {:>8.3}% {:>8.3}% {:>10}\n",
+                    s.tile.name(), s.count, s.ratio * 100.0, s.expected * 100.0, clustering,
+                );
+            }
+            Ok(Output { json: density_json(&sample), text })
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+// DB が必要なコマンドだけ接続する
+fn pool() -> DbPool {
+    database::create_pool()
+}
+
+fn done(message: String) -> Output {
+    Output { json: json!({ "ok": true, "message": message }), text: message }
+}
+
+// 対象のユーザーがいなければエラーにする
+fn require(found: bool, name: &str, message: String) -> Result<Output, String> {
+    if found {
+        Ok(done(message))
+    } else {
+        Err(format!("no such user: {}", name))
+    }
+}
+
+fn parse_coord(s: &str) -> Result<i64, String> {
+    s.parse().map_err(|_| format!("bad coordinate: {}", s))
+}
+
+fn parse_region(x0: &str, y0: &str, x1: &str, y1: &str, step: i64) -> Result<Region, String> {
+    let region = Region::new(parse_coord(x0)?, parse_coord(y0)?, parse_coord(x1)?, parse_coord(y1)?, step);
+    let cells = region.samples();
+    if cells > MAX_REGION_CELLS {
+        return Err(format!("region is too large ({} cells, max {}); use --step", cells, MAX_REGION_CELLS));
+    }
+    Ok(region)
+}
+
+fn density_json(sample: &Sample) -> Value {
+    let r = &sample.region;
+    json!({
+        "x0": r.x0, "y0": r.y0, "x1": r.x1, "y1": r.y1, "step": r.step,
+        "tiles": sample.stats().iter().map(|s| json!({
+            "tile": s.tile.name(),
+            "symbol": s.tile.ascii().to_string(),
+            "count": s.count,
+            "ratio": s.ratio,
+            "expected": s.expected,
+            "clustering": s.clustering,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn list_users(pool: &DbPool, pattern: Option<&str>, limit: i64) -> Result<Output, String> {
+    let users = database::list_users(pool, pattern, limit)?;
+    let json = json!(users.iter().map(user_json).collect::<Vec<_>>());
+    let mut text = format!("{:<12} {:>8} {:>8} {:>6} {:>5}\n", "USER", "X", "Y", "POINTS", "WORDS");
+    for u in &users {
+        text += &format!("{:<12} {:>8} {:>8} {:>6} {:>5}\n", u.username, u.x, u.y, u.points, u.word_count);
+    }
+    text += &format!("{} users", users.len());
+    Ok(Output { json, text })
+}
+
+fn user_json(u: &UserSummary) -> Value {
+    json!({ "username": u.username, "x": u.x, "y": u.y, "points": u.points, "words": u.word_count })
+}
+
+fn show_user(pool: &DbPool, name: &str) -> Result<Output, String> {
+    if !database::user_exists(pool, name)? {
+        return Err(format!("no such user: {}", name));
+    }
+    let profile = database::load_profile(pool, name)?;
+    let done_count = profile.quests.iter().filter(|q| q.done).count();
+    let text = format!(
+        "User:     {}\nPosition: ({}, {})\nPoints:   {}\nWords:    {}\nItems:    {}\nEmojis:   S={} D={} {}\nQuests:   {} done / {} total\nMuted:    {}\nExplored: {} cells ({} chunks remembered)",
+        name,
+        profile.x,
+        profile.y,
+        profile.points,
+        profile.words.join(", "),
+        profile.items.join(", "),
+        profile.s_key,
+        profile.d_key,
+        profile.emojis.join(" "),
+        done_count,
+        profile.quests.len(),
+        profile.muted.join(", "),
+        profile.explored_cells,
+        profile.explored.len(),
+    );
+    let mut json = serde_json::to_value(&profile).map_err(|e| e.to_string())?;
+    json["username"] = json!(name);
+    // チャンクのビット列は大きいので数だけにする
+    json["explored"] = json!(profile.explored.len());
+    Ok(Output { json, text })
+}
+
+// 【新規】探索したマス数の多い順
+fn leaderboard(pool: &DbPool, limit: i64) -> Result<Output, String> {
+    let rows = database::load_leaderboard(pool, limit)?;
+    let json = json!(rows.iter().enumerate().map(|(i, (username, cells, points))| json!({
+        "rank": i + 1, "username": username, "explored_cells": cells, "points": points,
+    })).collect::<Vec<_>>());
+    let mut text = format!("{:>4} {:<12} {:>12} {:>6}\n", "RANK", "USER", "EXPLORED", "POINTS");
+    for (i, (username, cells, points)) in rows.iter().enumerate() {
+        text += &format!("{:>4} {:<12} {:>12} {:>6}\n", i + 1, username, cells, points);
+    }
+    Ok(Output { json, text })
+}
+
+fn stats(pool: &DbPool) -> Result<Output, String> {
+    let s = database::load_stats(pool)?;
+    Ok(Output {
+        json: json!({
+            "users": s.users,
+            "total_points": s.total_points,
+            "quests_active": s.quests_active,
+            "quests_done": s.quests_done,
+            "bot_memories": s.bot_memories,
+            "online": s.online,
+            "violations_24h": s.violations_24h,
+        }),
+        text: format!(
+            "Users:          {}\nTotal points:   {}\nQuests:         {} active / {} done\nBot memories:   {}\nOnline (15s):   {}\nViolations 24h: {}",
+            s.users, s.total_points, s.quests_active, s.quests_done, s.bot_memories, s.online, s.violations_24h,
+        ),
+    })
+}
@@ -0,0 +1,32 @@
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use evolution_game::server::ServerPlugin;
+use evolution_game::web;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// ==========================================
+// 対戦サーバー (ウィンドウ・GPU なし)
+// cargo run --no-default-features --bin evol-server
+// 待ち受けアドレスは SERVER_BIND、DB は DB_HOST で指定する
+// 【新規】WEB_ROOT (または --web <dir>) を指定するとブラウザ版も WEB_BIND で配信する
+// ==========================================
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let web_root = args
+        .iter()
+        .position(|a| a == "--web")
+        .and_then(|i| args.get(i + 1).cloned())
+        .or_else(|| std::env::var("WEB_ROOT").ok());
+
+    if let Some(root) = web_root {
+        let bind = std::env::var("WEB_BIND").unwrap_or_else(|_| web::DEFAULT_WEB_BIND.to_string());
+        web::serve_static(&bind, PathBuf::from(&root)).expect("Failed to start web server.");
+        println!("Serving {} on http://{}", root, bind);
+    }
+
+    App::new()
+        .add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / 30.0))))
+        .add_plugins(ServerPlugin)
+        .run();
+}
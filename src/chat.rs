@@ -0,0 +1,36 @@
+// ==========================================
+// 【新規】プレイヤー同士の会話の連投制限
+// トークンバケット: 最大 CHAT_BURST 回まで続けて話せて、
+// 1 秒に CHAT_REFILL_PER_SEC 回分ずつ回復する。
+// クライアント (入力) とサーバー (Say / Emote の中継) の両方で使う
+// ==========================================
+
+pub const CHAT_BURST: f32 = 3.0;
+pub const CHAT_REFILL_PER_SEC: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimiter {
+    tokens: f32,
+    // 最後に allow を呼んだ時刻 (Time::elapsed_secs_f64。長く動かしても精度が落ちないように f64)
+    last: f64,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self { tokens: CHAT_BURST, last: 0.0 }
+    }
+}
+
+impl RateLimiter {
+    // now の時点で話してよいか（よければ 1 回分消費する）
+    pub fn allow(&mut self, now: f64) -> bool {
+        self.tokens = (self.tokens + (now - self.last).max(0.0) as f32 * CHAT_REFILL_PER_SEC).min(CHAT_BURST);
+        self.last = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use std::collections::HashMap;
+use crate::constants::TILE_SIZE;
 
 #[derive(Component)]
 pub struct Player;
@@ -9,17 +11,50 @@ pub struct GridPosition {
     pub y: i64,
 }
 
+// 【新規】ダッシュに使うスタミナ（上限は StaminaConfig）
+#[derive(Component)]
+pub struct Stamina(pub f32);
+
+// 【新規】見た目の位置（ピクセル）。固定ティックごとに GridPosition へ近づけ、
+// 描画はひとつ前のティックとの間を補間する（フレームレートで動きが変わらないように）
+#[derive(Component, Clone, Copy)]
+pub struct PixelMotion {
+    pub previous: Vec2,
+    pub current: Vec2,
+}
+
+impl PixelMotion {
+    pub fn at(x: i64, y: i64) -> Self {
+        let pos = Vec2::new(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE);
+        PixelMotion { previous: pos, current: pos }
+    }
+}
+
 #[derive(Component)]
 pub struct Vocabulary {
     pub words: Vec<String>,
 }
 
+// 【新規】持っているアイテム (アイテム表の id)
+#[derive(Component)]
+pub struct Inventory {
+    pub items: Vec<String>,
+}
+
+// 【新規】クエストなどで貯めたポイント
+#[derive(Component)]
+pub struct Points(pub i32);
+
 #[derive(Component)]
 pub struct PositionText;
 
 #[derive(Component)]
 pub struct DirectionText;
 
+// 【新規】HUD のスタミナバー（中身の部分）
+#[derive(Component)]
+pub struct StaminaBar;
+
 #[derive(Component)]
 pub struct ChatDisplay;
 
@@ -47,6 +82,17 @@ pub struct SaveButton;
 #[derive(Component)]
 pub struct GameEntity; 
 
+// 【新規】クエストログ ([Q] で開閉)
+#[derive(Component)]
+pub struct QuestLogDisplay;
+
+// 【新規】フィールドに落ちているアイテムの表示
+#[derive(Component)]
+pub struct ItemMarker {
+    pub x: i64,
+    pub y: i64,
+}
+
 // --- ボット関連 ---
 
 #[derive(Component)]
@@ -76,4 +122,107 @@ pub struct BotTalking(pub Timer);
 
 // 【新規】そのボット固有のセリフ（生成時に決定・固定）
 #[derive(Component)]
-pub struct BotDialogueText(pub String);
\ No newline at end of file
+pub struct BotDialogueText(pub String);
+
+// 【新規】ボットの性格（スポーン地点から決定的に決まる）
+// プレイヤーの絵文字への反応が変わる
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum BotPersonality {
+    Friendly,
+    Shy,
+    Grumpy,
+    Curious,
+}
+
+// 【新規】ボットが覚えているプレイヤー1人分の記憶
+#[derive(Default, Clone)]
+pub struct PlayerMemory {
+    pub times_greeted: i32,
+    // UNIX 時間 (秒)。0 = 会ったことがない
+    pub last_seen: i64,
+    // 好感度（言葉や絵文字の印象で増減）
+    pub affinity: i32,
+    // DB に未保存の変更がある
+    pub dirty: bool,
+}
+
+// 【新規】ボットの記憶（ユーザー名ごと）
+// DB からの読み込みはストリームイン後に遅延して行う (BotMemoryPending)
+#[derive(Component, Default)]
+pub struct BotMemory {
+    pub players: HashMap<String, PlayerMemory>,
+}
+
+// 【新規】記憶を DB から読み込む前のボット
+#[derive(Component)]
+pub struct BotMemoryPending;
+
+// 【新規】サーバーに記憶を問い合わせ中のボット（返事が来たら BotMemoryPending と一緒に外す）
+#[derive(Component)]
+pub struct BotMemoryRequested;
+
+// 【新規】一時的な移動目標（プレイヤーに近づく / 離れる）
+// steps_left 歩だけ徘徊の代わりに目標基準で動く
+#[derive(Component)]
+pub struct BotIntent {
+    pub target_x: i64,
+    pub target_y: i64,
+    pub approach: bool,
+    pub steps_left: u32,
+}
+
+// 【新規】ボットの体の上に出す絵文字（返事用）
+#[derive(Component)]
+pub struct BotEmojiText;
+// 【新規】サーバー側: 接続中のプレイヤー
+#[derive(Component)]
+pub struct NetPlayer {
+    pub id: crate::net::ClientId,
+    pub username: String,
+    // 最後に移動を認めた時刻 (Time::elapsed_secs_f64。サーバーは長く動くので f64)
+    pub last_move: f64,
+    // 【新規】言葉の連投制限とミュートしている相手
+    pub chat_limit: crate::chat::RateLimiter,
+    pub muted: std::collections::HashSet<String>,
+    // 【新規】最後に不正な移動を記録した時刻（連続した不正で DB があふれないように）
+    pub last_audit: f64,
+    // 【新規】探索済みの場所（サーバー接続中は認めた移動からサーバーが記録する）
+    pub explored: crate::explore::ExploredMap,
+    // 【新規】ダッシュの判定用のスタミナと、最後に回復させた時刻
+    pub stamina: f32,
+    pub stamina_at: f64,
+    // 【新規】最後に DB に書いた位置（定期保存で、動いた人だけ書く）
+    pub last_saved: (i64, i64),
+    // 【新規】手に入れた絵文字（Emote で出してよいものの判定に使う）
+    pub emojis: std::collections::HashSet<String>,
+    // 【新規】覚えた言葉（Say で話してよいものの判定に使う）
+    pub words: std::collections::HashSet<String>,
+}
+
+// 【新規】クライアント側: 他のプレイヤー（サーバーのスナップショットから作る）
+#[derive(Component)]
+pub struct RemotePlayer {
+    pub id: crate::net::ClientId,
+}
+
+// 【新規】他のプレイヤーの体の上に出す絵文字 / 吹き出し
+#[derive(Component)]
+pub struct RemotePlayerEmoji;
+
+#[derive(Component)]
+pub struct RemotePlayerChat;
+
+// 【新規】他のプレイヤーの見た目（RemotePlayer と PresencePlayer の共通部分）
+// name はミュートの対象を選ぶときに使う
+#[derive(Component)]
+pub struct OtherPlayer {
+    pub name: String,
+}
+
+// 【新規】クライアント側: DB の presence テーブルから見える他のプレイヤー（サーバーなしの場合）
+#[derive(Component)]
+pub struct PresencePlayer {
+    pub username: String,
+    // 最後に吹き出しを出した言葉の時刻（同じ言葉を何度も出さないため）
+    pub word_at: f64,
+}
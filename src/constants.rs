@@ -10,4 +10,45 @@ pub const FIELD_LIMIT: i64 = 1_000_000_000_000;
 
 // 【新規追加】プレイヤーの移動間隔 (秒)
 // ここが「聖域」として切り出された設定値です
-pub const PLAYER_MOVE_INTERVAL: f32 = 1.0;
\ No newline at end of file
+pub const PLAYER_MOVE_INTERVAL: f32 = 1.0;
+
+// 【新規】ゲームの処理 (FixedUpdate) を 1 秒に何回動かすか。TICK_RATE で変えられる
+pub const DEFAULT_TICK_RATE: f64 = 30.0;
+
+// 【新規】固定ティックの回数 (Hz)。クライアントとサーバーで同じ値を使う
+pub fn tick_rate_from_env() -> f64 {
+    std::env::var("TICK_RATE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+        .unwrap_or(DEFAULT_TICK_RATE)
+}
+
+// 【新規】ウェイポイント（保存した地点）の数と名前の長さの上限
+pub const MAX_WAYPOINTS: usize = 8;
+pub const MAX_WAYPOINT_NAME_LEN: usize = 16;
+
+// 【新規】声・絵文字が届く範囲 (縦横のマス数、正方形判定)
+pub const VOICE_RANGE: i64 = 4;
+
+// 【新規】ボットが言葉・絵文字を聞き取る範囲 (マス数、円形判定。元の TILE_SIZE * 4.5 と同じ広さ)
+pub const BOT_HEARING_RADIUS: f32 = 4.5;
+
+// 【新規】ボットの好感度の上限・下限（DB に足し合わせるときにも使う）
+pub const AFFINITY_LIMIT: i32 = 10;
+
+// 【新規】ゲーム内のランキングに出す人数
+pub const LEADERBOARD_SIZE: i64 = 10;
+
+// 【新規】ネットワーク対戦: サーバーがスナップショットに含める範囲 (縦横のマス数)
+// 画面 (800x600) に映る範囲より少し広め
+pub const NET_VIEW_RANGE: i64 = 12;
+
+// 【新規】スナップショットを送る間隔 (秒)
+pub const SNAPSHOT_INTERVAL: f32 = 0.2;
+
+// 【新規】カメラのズーム (OrthographicProjection の scale。大きいほど広く映る)
+pub const CAMERA_MIN_ZOOM: f32 = 0.5;
+pub const CAMERA_MAX_ZOOM: f32 = 3.0;
+// キー 1 回・ホイール 1 目盛りで変わる倍率
+pub const CAMERA_ZOOM_STEP: f32 = 1.25;
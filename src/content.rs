@@ -0,0 +1,129 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use std::fmt;
+
+// ==========================================
+// コンテンツファイル (assets/*.txt) を Bevy のアセットとして扱う
+// file_watcher 有効時はファイル保存で自動的に再読込される
+// ==========================================
+
+// 1行 = 1エントリのテキストアセット
+// 空行と '#' で始まるコメント行は読み込み時に取り除く
+#[derive(Asset, TypePath, Debug)]
+pub struct ContentText {
+    pub lines: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ContentTextLoader;
+
+#[derive(Debug)]
+pub enum ContentTextLoaderError {
+    Io(std::io::Error),
+    Utf8(std::string::FromUtf8Error),
+}
+
+impl fmt::Display for ContentTextLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "read error: {}", e),
+            Self::Utf8(e) => write!(f, "invalid UTF-8: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ContentTextLoaderError {}
+
+impl AssetLoader for ContentTextLoader {
+    type Asset = ContentText;
+    type Settings = ();
+    type Error = ContentTextLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(ContentTextLoaderError::Io)?;
+        let content = String::from_utf8(bytes).map_err(ContentTextLoaderError::Utf8)?;
+
+        Ok(ContentText { lines: content_lines(&content) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt"]
+    }
+}
+
+// 空行とコメント行を取り除いた各行
+fn content_lines(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && !s.starts_with('#'))
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// 【新規】アセットサーバーを使わずに読む（サーバーはアセットを読み込まないので、起動時にこれで読む）
+pub fn read_content_file(path: &std::path::Path) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(content_lines(&content))
+}
+
+// 絵文字リストの検証
+// 1行に1つ、空白を含まず DB の s_key/d_key カラム (VARCHAR(10)) に収まること
+pub fn parse_emoji_list(lines: &[String]) -> Result<Vec<String>, String> {
+    let mut emojis = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        if line.contains(char::is_whitespace) || line.chars().count() > 10 {
+            return Err(format!("entry {}: '{}' is not a single emoji", i + 1, line));
+        }
+        emojis.push(line.clone());
+    }
+    if emojis.is_empty() {
+        return Err("no emojis defined".to_string());
+    }
+    Ok(emojis)
+}
+
+// アイテム表の1行 (id|名前|絵文字)
+#[derive(Clone, Debug)]
+pub struct ItemDef {
+    pub id: String,
+    pub name: String,
+    pub emoji: String,
+}
+
+// アイテム表の検証
+// id は英数字と '_' のみ・重複不可 (DB の items カラムには id で保存する)
+pub fn parse_item_table(lines: &[String]) -> Result<Vec<ItemDef>, String> {
+    let mut items: Vec<ItemDef> = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let fields: Vec<&str> = line.split('|').map(|f| f.trim()).collect();
+        let [id, name, emoji] = fields[..] else {
+            return Err(format!("entry {}: expected 'id|name|emoji', got '{}'", i + 1, line));
+        };
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("entry {}: invalid id '{}'", i + 1, id));
+        }
+        if name.is_empty() || emoji.is_empty() {
+            return Err(format!("entry {}: name and emoji must not be empty", i + 1));
+        }
+        if items.iter().any(|item| item.id == id) {
+            return Err(format!("entry {}: duplicate id '{}'", i + 1, id));
+        }
+        items.push(ItemDef {
+            id: id.to_string(),
+            name: name.to_string(),
+            emoji: emoji.to_string(),
+        });
+    }
+    if items.is_empty() {
+        return Err("no items defined".to_string());
+    }
+    Ok(items)
+}
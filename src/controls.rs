@@ -0,0 +1,413 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// ==========================================
+// 【新規】操作の割り当て
+// 各システムはキーを直接読まず、Action（移動・言葉・絵文字・メニュー…）を ActionState から読む。
+// キーボードとゲームパッドのボタンをユーザーごとに割り当て直せて、users.key_bindings に保存する。
+// A → 👍 だけは仕様で固定（キーボードは変えられない）
+// ==========================================
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    // 【新規】押している間ダッシュ
+    Sprint,
+    // 押している間チャットメニュー（Space）
+    OpenMenu,
+    EmoteThumb,
+    EmoteS,
+    EmoteD,
+    // 言葉 1〜4 (0 始まり)
+    Speak(u8),
+    Confirm,
+    Cancel,
+    // ログイン画面の ID / パスワード切り替え
+    SwitchInput,
+    Mute,
+    QuestLog,
+    WorldMap,
+    // 【変更】ワールドマップを開いていればマップ、閉じていればカメラのズーム
+    ZoomIn,
+    ZoomOut,
+    // 【新規】ワールドマップで今いる場所をウェイポイントにする
+    AddWaypoint,
+    // 【新規】ウェイポイントの削除・割り当て画面で既定に戻す
+    Remove,
+    // 【新規】探索マス数のランキング
+    Leaderboard,
+    // 割り当て画面
+    Controls,
+}
+
+impl Action {
+    pub const ALL: [Action; 25] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Sprint,
+        Action::OpenMenu,
+        Action::EmoteThumb,
+        Action::EmoteS,
+        Action::EmoteD,
+        Action::Speak(0),
+        Action::Speak(1),
+        Action::Speak(2),
+        Action::Speak(3),
+        Action::Confirm,
+        Action::Cancel,
+        Action::SwitchInput,
+        Action::Mute,
+        Action::QuestLog,
+        Action::WorldMap,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::AddWaypoint,
+        Action::Remove,
+        Action::Leaderboard,
+        Action::Controls,
+    ];
+
+    // 保存に使う名前（変えないこと）
+    pub fn id(&self) -> String {
+        match self {
+            Action::Speak(n) => format!("Speak{}", n + 1),
+            other => format!("{:?}", other),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    // 割り当て画面に出す名前
+    pub fn label(&self) -> String {
+        match self {
+            Action::MoveUp => "Move Up".to_string(),
+            Action::MoveDown => "Move Down".to_string(),
+            Action::MoveLeft => "Move Left".to_string(),
+            Action::MoveRight => "Move Right".to_string(),
+            Action::Sprint => "Sprint (hold)".to_string(),
+            Action::OpenMenu => "Chat Menu (hold)".to_string(),
+            Action::EmoteThumb => "Emote Thumbs Up".to_string(),
+            Action::EmoteS => "Emote S".to_string(),
+            Action::EmoteD => "Emote D".to_string(),
+            Action::Speak(n) => format!("Word {}", n + 1),
+            Action::Confirm => "Confirm".to_string(),
+            Action::Cancel => "Cancel".to_string(),
+            Action::SwitchInput => "Switch Field".to_string(),
+            Action::Mute => "Mute Nearest".to_string(),
+            Action::QuestLog => "Quest Log".to_string(),
+            Action::WorldMap => "World Map".to_string(),
+            Action::ZoomIn => "Zoom In".to_string(),
+            Action::ZoomOut => "Zoom Out".to_string(),
+            Action::AddWaypoint => "Add Waypoint".to_string(),
+            Action::Remove => "Remove / Reset".to_string(),
+            Action::Leaderboard => "Leaderboard".to_string(),
+            Action::Controls => "Controls".to_string(),
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::MoveUp => vec![KeyCode::ArrowUp],
+            Action::MoveDown => vec![KeyCode::ArrowDown],
+            Action::MoveLeft => vec![KeyCode::ArrowLeft],
+            Action::MoveRight => vec![KeyCode::ArrowRight],
+            Action::Sprint => vec![KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            Action::OpenMenu => vec![KeyCode::Space],
+            Action::EmoteThumb => vec![FIXED_THUMB_KEY],
+            Action::EmoteS => vec![KeyCode::KeyS],
+            Action::EmoteD => vec![KeyCode::KeyD],
+            Action::Speak(0) => vec![KeyCode::Digit1],
+            Action::Speak(1) => vec![KeyCode::Digit2],
+            Action::Speak(2) => vec![KeyCode::Digit3],
+            Action::Speak(_) => vec![KeyCode::Digit4],
+            Action::Confirm => vec![KeyCode::Enter],
+            Action::Cancel => vec![KeyCode::Escape],
+            Action::SwitchInput => vec![KeyCode::Tab],
+            Action::Mute => vec![KeyCode::KeyM],
+            Action::QuestLog => vec![KeyCode::KeyQ],
+            Action::WorldMap => vec![KeyCode::KeyN],
+            Action::ZoomIn => vec![KeyCode::Equal, KeyCode::NumpadAdd],
+            Action::ZoomOut => vec![KeyCode::Minus, KeyCode::NumpadSubtract],
+            Action::AddWaypoint => vec![KeyCode::KeyP],
+            Action::Remove => vec![KeyCode::Backspace],
+            Action::Leaderboard => vec![KeyCode::KeyL],
+            Action::Controls => vec![KeyCode::KeyK],
+        }
+    }
+
+    fn default_buttons(&self) -> Vec<GamepadButton> {
+        match self {
+            Action::MoveUp => vec![GamepadButton::DPadUp],
+            Action::MoveDown => vec![GamepadButton::DPadDown],
+            Action::MoveLeft => vec![GamepadButton::DPadLeft],
+            Action::MoveRight => vec![GamepadButton::DPadRight],
+            Action::Sprint => vec![GamepadButton::LeftThumb],
+            Action::OpenMenu => vec![GamepadButton::LeftTrigger],
+            Action::EmoteThumb => vec![GamepadButton::RightTrigger],
+            Action::EmoteS => vec![GamepadButton::West],
+            Action::EmoteD => vec![GamepadButton::North],
+            Action::Confirm => vec![GamepadButton::South],
+            Action::Cancel => vec![GamepadButton::East],
+            Action::QuestLog => vec![GamepadButton::Select],
+            Action::WorldMap => vec![GamepadButton::Start],
+            Action::ZoomIn => vec![GamepadButton::RightTrigger2],
+            Action::ZoomOut => vec![GamepadButton::LeftTrigger2],
+            Action::Remove => vec![GamepadButton::RightThumb],
+            _ => Vec::new(),
+        }
+    }
+}
+
+// 【新規】左スティックをこれ以上倒したら、その向きの移動を押したことにする
+pub const STICK_THRESHOLD: f32 = 0.5;
+
+// スティックの傾きを移動の操作に変える（斜めは 2 つ）
+pub fn stick_actions(stick: Vec2) -> impl Iterator<Item = Action> {
+    [
+        (stick.y > STICK_THRESHOLD, Action::MoveUp),
+        (stick.y < -STICK_THRESHOLD, Action::MoveDown),
+        (stick.x < -STICK_THRESHOLD, Action::MoveLeft),
+        (stick.x > STICK_THRESHOLD, Action::MoveRight),
+    ]
+    .into_iter()
+    .filter_map(|(on, action)| on.then_some(action))
+}
+
+// 仕様で固定: A キーはいつでも 👍
+pub const FIXED_THUMB_KEY: KeyCode = KeyCode::KeyA;
+
+// 割り当てられるキー（保存は Debug 名。ここにないキーは読み込み時に捨てる）
+const BINDABLE_KEYS: [KeyCode; 83] = [
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF, KeyCode::KeyG,
+    KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL, KeyCode::KeyM, KeyCode::KeyN,
+    KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR, KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU,
+    KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX, KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+    KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::NumpadAdd, KeyCode::NumpadSubtract, KeyCode::NumpadEnter,
+    KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ArrowLeft, KeyCode::ArrowRight,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight,
+    KeyCode::AltLeft, KeyCode::AltRight,
+    KeyCode::Equal, KeyCode::Minus, KeyCode::Comma, KeyCode::Period, KeyCode::Slash, KeyCode::Semicolon,
+    KeyCode::Quote, KeyCode::BracketLeft, KeyCode::BracketRight, KeyCode::Backslash, KeyCode::Backquote,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8,
+];
+
+pub fn is_bindable_key(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.into_iter().find(|k| format!("{:?}", k) == name)
+}
+
+fn parse_button(name: &str) -> Option<GamepadButton> {
+    GamepadButton::all().into_iter().find(|b| format!("{:?}", b) == name)
+}
+
+// 画面に出すキーの名前 (KeyA → A, Digit1 → 1)
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(&name).to_string()
+}
+
+// 保存する形 { "MoveUp": { "keys": ["ArrowUp"], "buttons": ["DPadUp"] }, ... }
+#[derive(Serialize, Deserialize, Default)]
+struct SavedBinding {
+    #[serde(default)]
+    keys: Vec<String>,
+    #[serde(default)]
+    buttons: Vec<String>,
+}
+
+#[derive(Resource, Clone)]
+pub struct Controls {
+    keys: HashMap<Action, Vec<KeyCode>>,
+    buttons: HashMap<Action, Vec<GamepadButton>>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Controls {
+            keys: Action::ALL.iter().map(|a| (*a, a.default_keys())).collect(),
+            buttons: Action::ALL.iter().map(|a| (*a, a.default_buttons())).collect(),
+        }
+    }
+}
+
+impl Controls {
+    // 保存した割り当てを既定の上に重ねる（空・壊れていれば既定のまま）
+    // 【変更】bind_key と同じ決まりで入れる（A キーは 👍 だけ、同じキー・ボタンは後から入れた操作だけが使う）
+    pub fn from_saved(saved: &str) -> Self {
+        let mut controls = Controls::default();
+        let Ok(map) = serde_json::from_str::<BTreeMap<String, SavedBinding>>(saved) else { return controls };
+        for (id, binding) in map {
+            let Some(action) = Action::from_id(&id) else { continue };
+            if action == Action::EmoteThumb {
+                continue;
+            }
+            let keys: Vec<KeyCode> = binding.keys.iter().filter_map(|k| parse_key(k)).filter(|k| *k != FIXED_THUMB_KEY).collect();
+            let buttons: Vec<GamepadButton> = binding.buttons.iter().filter_map(|b| parse_button(b)).collect();
+            controls.set_bindings(action, keys, buttons);
+        }
+        controls
+    }
+
+    // 既定と違う割り当てだけを保存する
+    pub fn to_saved(&self) -> String {
+        let map: BTreeMap<String, SavedBinding> = Action::ALL
+            .iter()
+            .filter(|a| self.keys(**a) != a.default_keys() || self.buttons(**a) != a.default_buttons())
+            .map(|a| (a.id(), SavedBinding {
+                keys: self.keys(*a).iter().map(|k| format!("{:?}", k)).collect(),
+                buttons: self.buttons(*a).iter().map(|b| format!("{:?}", b)).collect(),
+            }))
+            .collect();
+        serde_json::to_string(&map).unwrap_or_default()
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn buttons(&self, action: Action) -> &[GamepadButton] {
+        self.buttons.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // 画面の案内用（最初のキー、なければ "-"）
+    pub fn key_label(&self, action: Action) -> String {
+        self.keys(action).first().map(|k| key_name(*k)).unwrap_or_else(|| "-".to_string())
+    }
+
+    // action を key だけにする。同じキーを使っていた他の操作からは外す
+    pub fn bind_key(&mut self, action: Action, key: KeyCode) -> Result<(), String> {
+        if action == Action::EmoteThumb || key == FIXED_THUMB_KEY {
+            return Err(format!("{} is reserved for 👍", key_name(FIXED_THUMB_KEY)));
+        }
+        for keys in self.keys.values_mut() {
+            keys.retain(|k| *k != key);
+        }
+        self.keys.insert(action, vec![key]);
+        Ok(())
+    }
+
+    pub fn bind_button(&mut self, action: Action, button: GamepadButton) {
+        for buttons in self.buttons.values_mut() {
+            buttons.retain(|b| *b != button);
+        }
+        self.buttons.insert(action, vec![button]);
+    }
+
+    // 【変更】既定に戻したキー・ボタンは他の操作から外す
+    pub fn reset(&mut self, action: Action) {
+        self.set_bindings(action, action.default_keys(), action.default_buttons());
+    }
+
+    // 【新規】action を keys / buttons にして、他の操作からは外す（同じものが 2 つあれば 1 つにする）
+    fn set_bindings(&mut self, action: Action, mut keys: Vec<KeyCode>, mut buttons: Vec<GamepadButton>) {
+        let mut seen_keys = HashSet::new();
+        keys.retain(|k| seen_keys.insert(*k));
+        let mut seen_buttons = HashSet::new();
+        buttons.retain(|b| seen_buttons.insert(*b));
+
+        for (other, other_keys) in self.keys.iter_mut() {
+            if *other != action {
+                other_keys.retain(|k| !keys.contains(k));
+            }
+        }
+        for (other, other_buttons) in self.buttons.iter_mut() {
+            if *other != action {
+                other_buttons.retain(|b| !buttons.contains(b));
+            }
+        }
+        self.keys.insert(action, keys);
+        self.buttons.insert(action, buttons);
+    }
+}
+
+// このフレームの操作の状態（キーボード・ゲームパッドのボタンとスティック・タッチをまとめたもの）
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    previous: HashSet<Action>,
+    // consume した操作（離すまで押していないことにする）
+    suppressed: HashSet<Action>,
+    // タッチのボタンなど、キー以外から押したことにする操作（毎フレーム入れ直す）
+    pub injected: HashSet<Action>,
+    // 【新規】このフレームに consume されたか（メニューなどが入力を止めている）
+    consumed: bool,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action) && !self.previous.contains(&action)
+    }
+
+    // 入力を読み直す（前のフレームの状態は just_pressed のために残す）
+    pub fn update(&mut self, mut pressed: HashSet<Action>) {
+        self.consumed = false;
+        self.suppressed.retain(|a| pressed.contains(a));
+        pressed.retain(|a| !self.suppressed.contains(a));
+        self.previous = std::mem::replace(&mut self.pressed, pressed);
+    }
+
+    // このフレームの入力をなかったことにする（割り当て画面を開いている間など）
+    // 押しっぱなしの操作は離すまで無視するので、閉じたときに誤動作しない
+    pub fn consume(&mut self) {
+        self.suppressed.extend(self.pressed.drain());
+        self.consumed = true;
+    }
+
+    // 【新規】consume されたか。update の前に読むと前のフレームの結果（開いているメニューがあるか）になる
+    pub fn consumed(&self) -> bool {
+        self.consumed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners(controls: &Controls, key: KeyCode) -> Vec<Action> {
+        Action::ALL.into_iter().filter(|a| controls.keys(*a).contains(&key)).collect()
+    }
+
+    // 保存データで A キーや同じキーを 2 つの操作に割り当てていても、読み込むと 1 つになる
+    #[test]
+    fn from_saved_strips_reserved_and_duplicate_keys() {
+        let saved = r#"{"Confirm":{"keys":["KeyA","KeyS","KeyS"]},"EmoteThumb":{"keys":["KeyZ"]},"Mute":{"buttons":["South"]}}"#;
+        let controls = Controls::from_saved(saved);
+        assert_eq!(owners(&controls, FIXED_THUMB_KEY), vec![Action::EmoteThumb]);
+        assert_eq!(owners(&controls, KeyCode::KeyS), vec![Action::Confirm]);
+        assert_eq!(controls.keys(Action::Confirm), &[KeyCode::KeyS]);
+        assert_eq!(controls.keys(Action::EmoteThumb), &[FIXED_THUMB_KEY]);
+        assert!(!controls.buttons(Action::Confirm).contains(&GamepadButton::South));
+    }
+
+    // 既定に戻すと、そのキーを使っていた他の操作からは外れる
+    #[test]
+    fn reset_takes_default_keys_back() {
+        let mut controls = Controls::default();
+        controls.bind_key(Action::Confirm, KeyCode::KeyS).unwrap();
+        controls.bind_button(Action::Confirm, GamepadButton::West);
+        controls.reset(Action::EmoteS);
+        assert_eq!(owners(&controls, KeyCode::KeyS), vec![Action::EmoteS]);
+        assert!(controls.buttons(Action::Confirm).is_empty());
+        assert_eq!(Controls::from_saved(&controls.to_saved()).keys(Action::EmoteS), &[KeyCode::KeyS]);
+    }
+}
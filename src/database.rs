@@ -1,6 +1,46 @@
 use r2d2::Pool;
 use r2d2_postgres::PostgresConnectionManager;
 use postgres::NoTls;
+use std::env; // 環境変数読み込み用
+use crate::net::UserProfile;
+use crate::explore::{ChunkBits, ChunkCount, CHUNK_WORDS, MAX_COUNTED_CHUNKS, MAX_EXPLORED_CHUNKS};
+use crate::stamina::StaminaConfig;
+use crate::constants::{AFFINITY_LIMIT, MAX_WAYPOINTS};
+use crate::map::is_teleport_stone;
+use crate::resources::Waypoint;
+use crate::quest::{Quest, QuestKind, QuestReward, SavedQuest};
+use postgres::GenericClient;
+
+// 【新規】保存のあいだの移動量の許容（通信の遅れ・保存タイミングのずれの分）
+const SAVE_DELTA_SLACK: i64 = 2;
+
+// 【変更】接続処理を setup から移動（サーバーモードでも使う）
+pub fn create_pool() -> Pool<PostgresConnectionManager<NoTls>> {
+    // 【修正】環境変数 DB_HOST があれば使い、なければ localhost を使う
+    // Docker内では "db"、ローカル開発では "localhost" になります
+    let db_host = env::var("DB_HOST").unwrap_or_else(|_| "localhost".to_string());
+    
+    // 接続文字列を動的に生成
+    let db_url = format!("host={} user=postgres password=password dbname=postgres", db_host);
+
+    // 【変更】evol-admin の JSON 出力を汚さないように標準エラーに出す
+    eprintln!("Connecting to DB at: {}", db_host);
+
+    let manager = PostgresConnectionManager::new(
+        db_url.parse().unwrap(),
+        NoTls,
+    );
+    
+    // 接続待機リトライロジックがないため、コンテナ起動順によっては即死する可能性がありますが
+    // restart: alwaysを入れているので再起動してつながります。
+    let pool = r2d2::Pool::new(manager).expect("Failed to create DB pool.");
+    
+    if let Err(e) = init_db(&pool) {
+        eprintln!("DB Init Error: {}", e);
+    }
+
+    pool
+}
 
 // DB初期化（テーブル作成）
 pub fn init_db(pool: &Pool<PostgresConnectionManager<NoTls>>) -> Result<(), String> {
@@ -21,10 +61,158 @@ pub fn init_db(pool: &Pool<PostgresConnectionManager<NoTls>>) -> Result<(), Stri
         )",
         &[],
     ).map_err(|e| e.to_string())?;
+
+    // 【新規】アイテム・ポイント・クエスト報酬の絵文字
+    client.batch_execute(
+        "ALTER TABLE users ADD COLUMN IF NOT EXISTS items TEXT[] DEFAULT '{}';
+         ALTER TABLE users ADD COLUMN IF NOT EXISTS points INT DEFAULT 0;
+         ALTER TABLE users ADD COLUMN IF NOT EXISTS emojis TEXT[] DEFAULT '{}';",
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】位置を最後に保存した時刻（保存のあいだにありえない距離を動いていないか調べる）
+    client.batch_execute(
+        "ALTER TABLE users ADD COLUMN IF NOT EXISTS position_saved_at TIMESTAMPTZ;",
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】不正な移動の記録 (kind: too_fast / invalid_step / save_delta)
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS move_audit (
+            id BIGSERIAL PRIMARY KEY,
+            username VARCHAR(50) NOT NULL,
+            kind VARCHAR(20) NOT NULL,
+            from_x BIGINT NOT NULL,
+            from_y BIGINT NOT NULL,
+            to_x BIGINT NOT NULL,
+            to_y BIGINT NOT NULL,
+            at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】クエストの進行状況
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS user_quests (
+            username VARCHAR(50) NOT NULL,
+            giver_x BIGINT NOT NULL,
+            giver_y BIGINT NOT NULL,
+            done BOOLEAN NOT NULL DEFAULT FALSE,
+            PRIMARY KEY (username, giver_x, giver_y)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】受けたときのクエストの内容 (quest::Quest の JSON)
+    // コンテンツを編集しても受けたクエストが変わらないように保存する
+    client.batch_execute(
+        "ALTER TABLE user_quests ADD COLUMN IF NOT EXISTS quest TEXT;",
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】拾ったアイテムのマス（ログインし直しても同じマスでは拾えない）
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS taken_items (
+            username VARCHAR(50) NOT NULL,
+            x BIGINT NOT NULL,
+            y BIGINT NOT NULL,
+            PRIMARY KEY (username, x, y)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】ボットの記憶 (ボットはスポーン地点で識別する)
+    // last_seen は UNIX 時間 (秒)
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS bot_memories (
+            bot_x BIGINT NOT NULL,
+            bot_y BIGINT NOT NULL,
+            username VARCHAR(50) NOT NULL,
+            times_greeted INT NOT NULL DEFAULT 0,
+            last_seen BIGINT NOT NULL DEFAULT 0,
+            affinity INT NOT NULL DEFAULT 0,
+            PRIMARY KEY (bot_x, bot_y, username)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】ミュート (username が muted の言葉を受け取らない)
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS user_mutes (
+            username VARCHAR(50) NOT NULL,
+            muted VARCHAR(50) NOT NULL,
+            PRIMARY KEY (username, muted)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】プレゼンス (同じ DB を使う他のクライアントに自分の居場所を知らせる)
+    // 時刻は DB の now() を使う（コンテナごとの時計のずれを気にしなくてよい）
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS presence (
+            username VARCHAR(50) PRIMARY KEY,
+            grid_x BIGINT NOT NULL,
+            grid_y BIGINT NOT NULL,
+            face TEXT NOT NULL DEFAULT '',
+            word TEXT,
+            word_at TIMESTAMPTZ,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】探索済みの場所 (bits はチャンク内の 1 マス 1 ビット、u64 を little endian で並べたもの)
+    // users.explored_cells は忘れたチャンクの分も含めた探索マス数
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS explored_chunks (
+            username VARCHAR(50) NOT NULL,
+            cx BIGINT NOT NULL,
+            cy BIGINT NOT NULL,
+            bits BYTEA NOT NULL,
+            touched_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (username, cx, cy)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+    client.batch_execute(
+        "ALTER TABLE users ADD COLUMN IF NOT EXISTS explored_cells BIGINT DEFAULT 0;",
+    ).map_err(|e| e.to_string())?;
+    // 【新規】忘れたチャンクで数え済みのマス数（もう一度歩いても数え直さないため）
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS explored_counted (
+            username VARCHAR(50) NOT NULL,
+            cx BIGINT NOT NULL,
+            cy BIGINT NOT NULL,
+            cells INT NOT NULL,
+            forgotten_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (username, cx, cy)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】操作の割り当て (既定と違うものだけの JSON)
+    client.batch_execute(
+        "ALTER TABLE users ADD COLUMN IF NOT EXISTS key_bindings TEXT DEFAULT '';",
+    ).map_err(|e| e.to_string())?;
+
+    // 【新規】ウェイポイント (1 人 MAX_WAYPOINTS 個まで)
+    client.execute(
+        "CREATE TABLE IF NOT EXISTS waypoints (
+            username VARCHAR(50) NOT NULL,
+            name TEXT NOT NULL,
+            grid_x BIGINT NOT NULL,
+            grid_y BIGINT NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+            PRIMARY KEY (username, name)
+        )",
+        &[],
+    ).map_err(|e| e.to_string())?;
     
     Ok(())
 }
 
+// 【新規】ユーザー名・パスワードに使える文字（ログイン画面の入力と同じ制限）
+pub fn is_valid_credential(s: &str) -> bool {
+    !s.is_empty() && s.len() <= 12 && s.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
 // ユーザー作成 (初期データも登録)
 pub fn create_user(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, password: &str) -> Result<(), String> {
     let mut client = pool.get().map_err(|e| e.to_string())?;
@@ -35,8 +223,8 @@ pub fn create_user(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str
     ];
 
     client.execute(
-        "INSERT INTO users (username, password, grid_x, grid_y, words, s_key, d_key) 
-         VALUES ($1, $2, 0, 0, $3, '😁', '😭')",
+        "INSERT INTO users (username, password, grid_x, grid_y, words, s_key, d_key, position_saved_at) 
+         VALUES ($1, $2, 0, 0, $3, '😁', '😭', now())",
         &[&username, &password, &initial_words],
     ).map_err(|e| e.to_string())?;
     
@@ -62,26 +250,43 @@ pub fn verify_user(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str
     Ok(count > 0)
 }
 
+// 【変更】ロードするカラムが増えたのでタプルから構造体にした
+pub struct UserData {
+    pub x: i64,
+    pub y: i64,
+    pub words: Vec<String>,
+    pub s_key: String,
+    pub d_key: String,
+    pub items: Vec<String>,
+    pub points: i32,
+    pub emojis: Vec<String>,
+}
+
 // 【新規】データのロード
-// 戻り値: (x, y, words, s_key, d_key)
-pub fn load_user_data(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<(i64, i64, Vec<String>, String, String), String> {
+pub fn load_user_data(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<UserData, String> {
     let mut client = pool.get().map_err(|e| e.to_string())?;
     
     let row = client.query_one(
-        "SELECT grid_x, grid_y, words, s_key, d_key FROM users WHERE username = $1",
+        "SELECT grid_x, grid_y, words, s_key, d_key,
+                COALESCE(items, '{}'), COALESCE(points, 0), COALESCE(emojis, '{}')
+         FROM users WHERE username = $1",
         &[&username],
     ).map_err(|e| e.to_string())?;
 
-    let x: i64 = row.get(0);
-    let y: i64 = row.get(1);
-    let words: Vec<String> = row.get(2);
-    let s_key: String = row.get(3);
-    let d_key: String = row.get(4);
-
-    Ok((x, y, words, s_key, d_key))
+    Ok(UserData {
+        x: row.get(0),
+        y: row.get(1),
+        words: row.get(2),
+        s_key: row.get(3),
+        d_key: row.get(4),
+        items: row.get(5),
+        points: row.get(6),
+        emojis: row.get(7),
+    })
 }
 
 // 【新規】データのセーブ
+// 【変更】戻り値: 位置が認められなかったときは DB に残っている位置（ほかの項目は書く）
 pub fn save_user_data(
     pool: &Pool<PostgresConnectionManager<NoTls>>, 
     username: &str, 
@@ -89,14 +294,780 @@ pub fn save_user_data(
     y: i64, 
     words: Vec<String>, 
     s_key: &str, 
-    d_key: &str
-) -> Result<(), String> {
+    d_key: &str,
+    items: Vec<String>,
+) -> Result<Option<(i64, i64)>, String> {
     let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
     
+    // 【変更】位置は update_position で確かめてから書く
+    tx.execute(
+        "UPDATE users SET words = $1, s_key = $2, d_key = $3, items = $4 WHERE username = $5",
+        &[&words, &s_key, &d_key, &items, &username],
+    ).map_err(|e| e.to_string())?;
+    let rejected = update_position(&mut tx, username, x, y)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(rejected)
+}
+
+// 【新規】位置と絵文字の割り当てだけを保存（サーバーが SaveProgress で使う。言葉と持ち物はサーバーが書く）
+// 戻り値は save_user_data と同じ
+pub fn save_user_keys(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    username: &str,
+    x: i64,
+    y: i64,
+    s_key: &str,
+    d_key: &str,
+) -> Result<Option<(i64, i64)>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    tx.execute(
+        "UPDATE users SET s_key = $1, d_key = $2 WHERE username = $3",
+        &[&s_key, &d_key, &username],
+    ).map_err(|e| e.to_string())?;
+    let rejected = update_position(&mut tx, username, x, y)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(rejected)
+}
+
+// 【新規】位置だけを保存（サーバーが接続中のプレイヤーについて使う）
+// 【変更】戻り値は save_user_data と同じ
+pub fn save_user_position(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, x: i64, y: i64) -> Result<Option<(i64, i64)>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    let rejected = update_position(&mut tx, username, x, y)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(rejected)
+}
+
+// 【新規】ログインしたときに、移動量の判定の起点を今にする
+// (ログアウトしていた時間を歩いていた時間に数えない)
+pub fn start_session(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
     client.execute(
-        "UPDATE users SET grid_x = $1, grid_y = $2, words = $3, s_key = $4, d_key = $5 WHERE username = $6",
-        &[&x, &y, &words, &s_key, &d_key, &username],
+        "UPDATE users SET position_saved_at = now() WHERE username = $1",
+        &[&username],
     ).map_err(|e| e.to_string())?;
-    
     Ok(())
-}
\ No newline at end of file
+}
+
+// 【新規】前回の保存からの経過時間で歩ける距離を超えていなければ位置を書く
+// 超えていたら位置は前回のままにして move_audit に記録する
+// 【変更】戻り値: 書かなかったときは前回の位置
+fn update_position(client: &mut impl GenericClient, username: &str, x: i64, y: i64) -> Result<Option<(i64, i64)>, String> {
+    let row = client.query_opt(
+        "SELECT grid_x, grid_y, EXTRACT(EPOCH FROM now() - position_saved_at)::FLOAT8
+         FROM users WHERE username = $1 FOR UPDATE",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+    let Some(row) = row else { return Err(format!("No such user: {}", username)) };
+
+    let old_x: i64 = row.get::<_, Option<i64>>(0).unwrap_or(0);
+    let old_y: i64 = row.get::<_, Option<i64>>(1).unwrap_or(0);
+    // 列を追加する前からいるユーザーは一度だけ確かめずに書く
+    if let Some(elapsed) = row.get::<_, Option<f64>>(2) {
+        // 【変更】ダッシュの分も含める
+        let max_steps = StaminaConfig::from_env().max_steps(elapsed).ceil() as i64 + SAVE_DELTA_SLACK;
+        if (x - old_x).abs().max((y - old_y).abs()) > max_steps {
+            eprintln!("Rejected position save ({}): ({}, {}) -> ({}, {})", username, old_x, old_y, x, y);
+            insert_violation(client, username, "save_delta", (old_x, old_y), (x, y))?;
+            return Ok(Some((old_x, old_y)));
+        }
+    }
+
+    client.execute(
+        "UPDATE users SET grid_x = $1, grid_y = $2, position_saved_at = now() WHERE username = $3",
+        &[&x, &y, &username],
+    ).map_err(|e| e.to_string())?;
+    Ok(None)
+}
+
+fn insert_violation(client: &mut impl GenericClient, username: &str, kind: &str, from: (i64, i64), to: (i64, i64)) -> Result<(), String> {
+    client.execute(
+        "INSERT INTO move_audit (username, kind, from_x, from_y, to_x, to_y) VALUES ($1, $2, $3, $4, $5, $6)",
+        &[&username, &kind, &from.0, &from.1, &to.0, &to.1],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 【新規】不正な移動を記録する（サーバーが移動を拒否したとき）
+pub fn log_violation(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, kind: &str, from: (i64, i64), to: (i64, i64)) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    insert_violation(&mut *client, username, kind, from, to)
+}
+
+// 【新規】ボットの記憶のロード
+// 戻り値: (username, times_greeted, last_seen, affinity) のリスト
+pub fn load_bot_memories(pool: &Pool<PostgresConnectionManager<NoTls>>, bot_x: i64, bot_y: i64) -> Result<Vec<(String, i32, i64, i32)>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT username, times_greeted, last_seen, affinity FROM bot_memories WHERE bot_x = $1 AND bot_y = $2",
+        &[&bot_x, &bot_y],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1), row.get(2), row.get(3))).collect())
+}
+
+// 【新規】ボットの記憶のセーブ (1ボット × 1ユーザー分を upsert)
+pub fn save_bot_memory(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    bot_x: i64,
+    bot_y: i64,
+    username: &str,
+    times_greeted: i32,
+    last_seen: i64,
+    affinity: i32,
+) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    client.execute(
+        "INSERT INTO bot_memories (bot_x, bot_y, username, times_greeted, last_seen, affinity)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (bot_x, bot_y, username)
+         DO UPDATE SET times_greeted = $4, last_seen = $5, affinity = $6",
+        &[&bot_x, &bot_y, &username, &times_greeted, &last_seen, &affinity],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 【新規】ボットの記憶を DB の内容に足し合わせる（クライアントの merge_bot_memory_rows と同じ足し方）
+// 記憶を読み込む前に消えるボットの分を書くときに使う
+pub fn add_bot_memory(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    bot_x: i64,
+    bot_y: i64,
+    username: &str,
+    times_greeted: i32,
+    last_seen: i64,
+    affinity: i32,
+) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    client.execute(
+        "INSERT INTO bot_memories (bot_x, bot_y, username, times_greeted, last_seen, affinity)
+         VALUES ($1, $2, $3, $4, $5, LEAST(GREATEST($6::INT, -$7::INT), $7::INT))
+         ON CONFLICT (bot_x, bot_y, username)
+         DO UPDATE SET times_greeted = bot_memories.times_greeted + $4,
+                       last_seen = GREATEST(bot_memories.last_seen, $5),
+                       affinity = LEAST(GREATEST(bot_memories.affinity + $6, -$7), $7)",
+        &[&bot_x, &bot_y, &username, &times_greeted, &last_seen, &affinity, &AFFINITY_LIMIT],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 【新規】クエストのロード
+// 【変更】保存した内容も読む（読めないものは None にして、座標から作り直してもらう）
+pub fn load_quests(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<Vec<SavedQuest>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT giver_x, giver_y, done, quest FROM user_quests WHERE username = $1",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SavedQuest {
+            giver_x: row.get(0),
+            giver_y: row.get(1),
+            done: row.get(2),
+            quest: row.get::<_, Option<String>>(3).and_then(|json| serde_json::from_str(&json).ok()),
+        })
+        .collect())
+}
+
+// 【新規】クエストの受注
+// 【変更】内容も保存する（受注済みなら最初の内容のまま）
+pub fn accept_quest(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, quest: &Quest) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(quest).map_err(|e| e.to_string())?;
+
+    client.execute(
+        "INSERT INTO user_quests (username, giver_x, giver_y, done, quest) VALUES ($1, $2, $3, FALSE, $4)
+         ON CONFLICT (username, giver_x, giver_y) DO NOTHING",
+        &[&username, &quest.giver_x, &quest.giver_y, &json],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 【新規】拾ったアイテムのマス
+pub fn load_taken_items(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<Vec<(i64, i64)>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT x, y FROM taken_items WHERE username = $1",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+// 【新規】アイテムを拾う
+// マスの記録と持ち物への追加を1つのトランザクションで行う (戻り値: 拾えたか。拾ったことのあるマスなら false)
+pub fn take_item(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    username: &str,
+    x: i64,
+    y: i64,
+    item_id: &str,
+) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    let inserted = tx.execute(
+        "INSERT INTO taken_items (username, x, y) VALUES ($1, $2, $3)
+         ON CONFLICT (username, x, y) DO NOTHING",
+        &[&username, &x, &y],
+    ).map_err(|e| e.to_string())?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    tx.execute(
+        "UPDATE users SET items = array_append(COALESCE(items, '{}'), $1) WHERE username = $2",
+        &[&item_id, &username],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+// 【新規】受けたクエストを1つ読む（受けていなければ None）
+pub fn load_quest(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, giver_x: i64, giver_y: i64) -> Result<Option<SavedQuest>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let row = client.query_opt(
+        "SELECT done, quest FROM user_quests WHERE username = $1 AND giver_x = $2 AND giver_y = $3",
+        &[&username, &giver_x, &giver_y],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(row.map(|row| SavedQuest {
+        giver_x,
+        giver_y,
+        done: row.get(0),
+        quest: row.get::<_, Option<String>>(1).and_then(|json| serde_json::from_str(&json).ok()),
+    }))
+}
+
+// 【新規】クエストの達成
+// 達成済みにするのと報酬の反映を1つのトランザクションで行う
+// (SAVE を押す前に落ちても、報酬だけ消えて達成済みになることがないように)
+// 【変更】報酬はクライアントの持ち物を書くのではなく、DB の持ち物に quest の報酬を足す。
+// 受けていない・達成済み・届けるアイテムを持っていないときは何もしない (戻り値: 達成できたか)
+pub fn complete_quest(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, quest: &Quest) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    let updated = tx.execute(
+        "UPDATE user_quests SET done = TRUE WHERE username = $1 AND giver_x = $2 AND giver_y = $3 AND NOT done",
+        &[&username, &quest.giver_x, &quest.giver_y],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Ok(false);
+    }
+
+    let row = tx.query_one(
+        "SELECT COALESCE(words, '{}'), COALESCE(items, '{}'), COALESCE(emojis, '{}') FROM users WHERE username = $1 FOR UPDATE",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+    let mut words: Vec<String> = row.get(0);
+    let mut items: Vec<String> = row.get(1);
+    let mut emojis: Vec<String> = row.get(2);
+
+    if let QuestKind::Bring { item_id } = &quest.kind {
+        let Some(index) = items.iter().position(|i| i == item_id) else { return Ok(false) };
+        items.remove(index);
+    }
+    let mut points = 0;
+    match &quest.reward {
+        QuestReward::Word(word) => {
+            if !words.contains(word) {
+                words.push(word.clone());
+            }
+        }
+        QuestReward::Emoji(emoji) => {
+            if !emojis.contains(emoji) {
+                emojis.push(emoji.clone());
+            }
+        }
+        QuestReward::Points(p) => points = *p,
+    }
+
+    tx.execute(
+        "UPDATE users SET words = $1, items = $2, emojis = $3, points = COALESCE(points, 0) + $4 WHERE username = $5",
+        &[&words, &items, &emojis, &points, &username],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+// 【新規】ログイン時に渡すセーブデータ一式（ローカルのログインとサーバーで共通）
+pub fn load_profile(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<UserProfile, String> {
+    let data = load_user_data(pool, username)?;
+    let quests = load_quests(pool, username).unwrap_or_else(|e| {
+        eprintln!("Quest Load Error: {}", e);
+        Vec::new()
+    });
+    let muted = load_mutes(pool, username).unwrap_or_else(|e| {
+        eprintln!("Mute Load Error: {}", e);
+        Vec::new()
+    });
+    let (explored, explored_counted, explored_cells) = load_explored(pool, username).unwrap_or_else(|e| {
+        eprintln!("Explored Load Error: {}", e);
+        (Vec::new(), Vec::new(), 0)
+    });
+    let key_bindings = load_bindings(pool, username).unwrap_or_else(|e| {
+        eprintln!("Bindings Load Error: {}", e);
+        String::new()
+    });
+    let waypoints = load_waypoints(pool, username).unwrap_or_else(|e| {
+        eprintln!("Waypoint Load Error: {}", e);
+        Vec::new()
+    });
+    let taken_items = load_taken_items(pool, username).unwrap_or_else(|e| {
+        eprintln!("Taken Items Load Error: {}", e);
+        Vec::new()
+    });
+
+    Ok(UserProfile {
+        x: data.x,
+        y: data.y,
+        words: data.words,
+        s_key: data.s_key,
+        d_key: data.d_key,
+        items: data.items,
+        points: data.points,
+        emojis: data.emojis,
+        quests,
+        muted,
+        explored,
+        explored_counted,
+        explored_cells,
+        key_bindings,
+        waypoints,
+        taken_items,
+    })
+}
+
+// 【新規】操作の割り当て
+pub fn load_bindings(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<String, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let row = client.query_one(
+        "SELECT COALESCE(key_bindings, '') FROM users WHERE username = $1",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+    Ok(row.get(0))
+}
+
+pub fn save_bindings(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, bindings: &str) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    client.execute(
+        "UPDATE users SET key_bindings = $1 WHERE username = $2",
+        &[&bindings, &username],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 【新規】ウェイポイント（作った順）
+pub fn load_waypoints(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<Vec<Waypoint>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let rows = client.query(
+        "SELECT name, grid_x, grid_y FROM waypoints WHERE username = $1 ORDER BY created_at, name",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+    Ok(rows.iter().map(|row| Waypoint { name: row.get(0), x: row.get(1), y: row.get(2) }).collect())
+}
+
+// 同じ名前があれば置き直す。新しく作るときは MAX_WAYPOINTS 個まで
+pub fn add_waypoint(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, name: &str, x: i64, y: i64) -> Result<(), String> {
+    if !Waypoint::is_valid_name(name) {
+        return Err("Invalid waypoint name".to_string());
+    }
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    // 同じユーザーの同時の追加で上限を超えないように、ユーザーの行をロックする
+    tx.execute("SELECT 1 FROM users WHERE username = $1 FOR UPDATE", &[&username]).map_err(|e| e.to_string())?;
+    let updated = tx.execute(
+        "UPDATE waypoints SET grid_x = $1, grid_y = $2 WHERE username = $3 AND name = $4",
+        &[&x, &y, &username, &name],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        let count: i64 = tx.query_one("SELECT COUNT(*) FROM waypoints WHERE username = $1", &[&username])
+            .map_err(|e| e.to_string())?
+            .get(0);
+        if count >= MAX_WAYPOINTS as i64 {
+            return Err(format!("Waypoint limit reached ({})", MAX_WAYPOINTS));
+        }
+        tx.execute(
+            "INSERT INTO waypoints (username, name, grid_x, grid_y) VALUES ($1, $2, $3, $4)",
+            &[&username, &name, &x, &y],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn delete_waypoint(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, name: &str) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    client.execute("DELETE FROM waypoints WHERE username = $1 AND name = $2", &[&username, &name])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// from がテレポート石ならウェイポイント name へ位置を移す（保存間の移動量チェックの起点も更新する）
+pub fn teleport_to_waypoint(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, name: &str, from: (i64, i64)) -> Result<(i64, i64), String> {
+    if !is_teleport_stone(from.0, from.1) {
+        return Err("Stand on a teleport stone to travel".to_string());
+    }
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let row = client.query_opt(
+        "SELECT grid_x, grid_y FROM waypoints WHERE username = $1 AND name = $2",
+        &[&username, &name],
+    ).map_err(|e| e.to_string())?;
+    let Some(row) = row else { return Err(format!("No waypoint named {}", name)) };
+    let (x, y): (i64, i64) = (row.get(0), row.get(1));
+    if !teleport_user(pool, username, x, y)? {
+        return Err(format!("No such user: {}", username));
+    }
+    Ok((x, y))
+}
+
+// 【新規】ミュートしているユーザー名のリスト
+pub fn load_mutes(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<Vec<String>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT muted FROM user_mutes WHERE username = $1 ORDER BY muted",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+// 【新規】ミュートの設定・解除
+pub fn set_muted(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, target: &str, muted: bool) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    if muted {
+        client.execute(
+            "INSERT INTO user_mutes (username, muted) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            &[&username, &target],
+        ).map_err(|e| e.to_string())?;
+    } else {
+        client.execute(
+            "DELETE FROM user_mutes WHERE username = $1 AND muted = $2",
+            &[&username, &target],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// 【新規】プレゼンスの 1 行
+pub struct PresenceRow {
+    pub username: String,
+    pub x: i64,
+    pub y: i64,
+    pub face: String,
+    pub word: Option<String>,
+    // word を話した時刻 (UNIX 秒)。言葉がなければ 0
+    pub word_at: f64,
+    // 最後の更新からの秒数
+    pub age: f64,
+}
+
+// 【新規】プレゼンスの更新 (word が Some なら話した言葉も更新する)
+pub fn save_presence(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    username: &str,
+    x: i64,
+    y: i64,
+    face: &str,
+    word: Option<&str>,
+) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    client.execute(
+        "INSERT INTO presence (username, grid_x, grid_y, face, word, word_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, CASE WHEN $5::TEXT IS NULL THEN NULL ELSE now() END, now())
+         ON CONFLICT (username)
+         DO UPDATE SET grid_x = $2, grid_y = $3, face = $4, updated_at = now(),
+             word = COALESCE($5, presence.word),
+             word_at = CASE WHEN $5::TEXT IS NULL THEN presence.word_at ELSE now() END",
+        &[&username, &x, &y, &face, &word],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 【新規】(x, y) の周囲 range マスにいる、max_age 秒以内に更新された他のプレイヤー
+pub fn load_presence(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    username: &str,
+    x: i64,
+    y: i64,
+    range: i64,
+    max_age: f64,
+) -> Result<Vec<PresenceRow>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT username, grid_x, grid_y, face, word,
+                COALESCE(EXTRACT(EPOCH FROM word_at)::FLOAT8, 0),
+                EXTRACT(EPOCH FROM now() - updated_at)::FLOAT8
+         FROM presence
+         WHERE username <> $1
+           AND grid_x BETWEEN $2 - $4 AND $2 + $4
+           AND grid_y BETWEEN $3 - $4 AND $3 + $4
+           AND updated_at > now() - make_interval(secs => $5)",
+        &[&username, &x, &y, &range, &max_age],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| PresenceRow {
+        username: row.get(0),
+        x: row.get(1),
+        y: row.get(2),
+        face: row.get(3),
+        word: row.get(4),
+        word_at: row.get(5),
+        age: row.get(6),
+    }).collect())
+}
+
+// 【新規】探索済みのチャンク（古い順）と探索マス数
+pub fn load_explored(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<(Vec<ChunkBits>, Vec<ChunkCount>, i64), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT cx, cy, bits FROM explored_chunks WHERE username = $1 ORDER BY touched_at",
+        &[&username],
+    ).map_err(|e| e.to_string())?;
+    // 壊れた行（長さが違う）は読み飛ばす
+    let chunks = rows.iter().filter_map(|row| {
+        let bytes: Vec<u8> = row.get(2);
+        if bytes.len() != CHUNK_WORDS * 8 {
+            return None;
+        }
+        let mut bits = [0u64; CHUNK_WORDS];
+        for (word, b) in bits.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(b.try_into().unwrap());
+        }
+        Some(ChunkBits { cx: row.get(0), cy: row.get(1), bits })
+    }).collect();
+
+    let counted = client.query(
+        "SELECT cx, cy, cells FROM explored_counted WHERE username = $1 ORDER BY forgotten_at",
+        &[&username],
+    ).map_err(|e| e.to_string())?.iter().map(|row| ChunkCount { cx: row.get(0), cy: row.get(1), cells: row.get(2) }).collect();
+
+    let cells = client.query_opt(
+        "SELECT COALESCE(explored_cells, 0) FROM users WHERE username = $1",
+        &[&username],
+    ).map_err(|e| e.to_string())?.map(|row| row.get(0)).unwrap_or(0);
+
+    Ok((chunks, counted, cells))
+}
+
+// 【新規】変わったチャンクを書き、上限を超えた古いチャンクを消す
+// 探索マス数は減らさない（別の端末で先に進んでいることがあるので大きい方を残す）
+// 【変更】忘れたチャンクの数え済みマス数も書く（こちらも上限を超えた古いものを消す）
+pub fn save_explored(
+    pool: &Pool<PostgresConnectionManager<NoTls>>,
+    username: &str,
+    chunks: &[ChunkBits],
+    counted: &[ChunkCount],
+    cells: i64,
+) -> Result<(), String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    for chunk in chunks {
+        let bytes: Vec<u8> = chunk.bits.iter().flat_map(|w| w.to_le_bytes()).collect();
+        tx.execute(
+            "INSERT INTO explored_chunks (username, cx, cy, bits, touched_at) VALUES ($1, $2, $3, $4, now())
+             ON CONFLICT (username, cx, cy) DO UPDATE SET bits = EXCLUDED.bits, touched_at = now()",
+            &[&username, &chunk.cx, &chunk.cy, &bytes],
+        ).map_err(|e| e.to_string())?;
+    }
+    let limit = MAX_EXPLORED_CHUNKS as i64;
+    tx.execute(
+        "DELETE FROM explored_chunks WHERE username = $1 AND (cx, cy) NOT IN (
+             SELECT cx, cy FROM explored_chunks WHERE username = $1 ORDER BY touched_at DESC LIMIT $2
+         )",
+        &[&username, &limit],
+    ).map_err(|e| e.to_string())?;
+    for count in counted {
+        tx.execute(
+            "INSERT INTO explored_counted (username, cx, cy, cells, forgotten_at) VALUES ($1, $2, $3, $4, now())
+             ON CONFLICT (username, cx, cy) DO UPDATE SET cells = GREATEST(explored_counted.cells, EXCLUDED.cells), forgotten_at = now()",
+            &[&username, &count.cx, &count.cy, &count.cells],
+        ).map_err(|e| e.to_string())?;
+    }
+    if !counted.is_empty() {
+        let limit = MAX_COUNTED_CHUNKS as i64;
+        tx.execute(
+            "DELETE FROM explored_counted WHERE username = $1 AND (cx, cy) NOT IN (
+                 SELECT cx, cy FROM explored_counted WHERE username = $1 ORDER BY forgotten_at DESC LIMIT $2
+             )",
+            &[&username, &limit],
+        ).map_err(|e| e.to_string())?;
+    }
+    tx.execute(
+        "UPDATE users SET explored_cells = GREATEST(COALESCE(explored_cells, 0), $1) WHERE username = $2",
+        &[&cells, &username],
+    ).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ==========================================
+// 【新規】管理用 (evol-admin)
+// ==========================================
+
+pub struct UserSummary {
+    pub username: String,
+    pub x: i64,
+    pub y: i64,
+    pub points: i32,
+    pub word_count: i32,
+}
+
+// pattern があればユーザー名の部分一致（大文字小文字を区別しない）で絞り込む
+pub fn list_users(pool: &Pool<PostgresConnectionManager<NoTls>>, pattern: Option<&str>, limit: i64) -> Result<Vec<UserSummary>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let pattern = format!("%{}%", pattern.unwrap_or(""));
+
+    let rows = client.query(
+        "SELECT username, COALESCE(grid_x, 0), COALESCE(grid_y, 0), COALESCE(points, 0),
+                COALESCE(cardinality(words), 0)
+         FROM users WHERE username ILIKE $1 ORDER BY username LIMIT $2",
+        &[&pattern, &limit],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| UserSummary {
+        username: row.get(0),
+        x: row.get(1),
+        y: row.get(2),
+        points: row.get(3),
+        word_count: row.get(4),
+    }).collect())
+}
+
+// 【新規】探索マス数のランキング (username, explored_cells, points)
+pub fn load_leaderboard(pool: &Pool<PostgresConnectionManager<NoTls>>, limit: i64) -> Result<Vec<(String, i64, i32)>, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+
+    let rows = client.query(
+        "SELECT username, COALESCE(explored_cells, 0), COALESCE(points, 0)
+         FROM users ORDER BY COALESCE(explored_cells, 0) DESC, username LIMIT $1",
+        &[&limit],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect())
+}
+
+// ユーザーと関連する行をすべて消す (戻り値: ユーザーがいたか)
+pub fn delete_user(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let mut tx = client.transaction().map_err(|e| e.to_string())?;
+
+    for table in ["user_quests", "bot_memories", "presence", "move_audit", "explored_chunks", "explored_counted", "waypoints", "taken_items"] {
+        tx.execute(&format!("DELETE FROM {} WHERE username = $1", table), &[&username]).map_err(|e| e.to_string())?;
+    }
+    tx.execute("DELETE FROM user_mutes WHERE username = $1 OR muted = $1", &[&username]).map_err(|e| e.to_string())?;
+    let deleted = tx.execute("DELETE FROM users WHERE username = $1", &[&username]).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(deleted > 0)
+}
+
+pub fn set_password(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, password: &str) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let updated = client.execute(
+        "UPDATE users SET password = $1 WHERE username = $2",
+        &[&password, &username],
+    ).map_err(|e| e.to_string())?;
+    Ok(updated > 0)
+}
+
+// 位置を書き換える（保存間の移動量チェックは通さない。次のチェックの起点も更新する）
+pub fn teleport_user(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, x: i64, y: i64) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let updated = client.execute(
+        "UPDATE users SET grid_x = $1, grid_y = $2, position_saved_at = now() WHERE username = $3",
+        &[&x, &y, &username],
+    ).map_err(|e| e.to_string())?;
+    Ok(updated > 0)
+}
+
+// 言葉を語彙の末尾に足す（持っていれば何もしない）
+pub fn grant_word(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, word: &str) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let updated = client.execute(
+        "UPDATE users SET words = array_append(COALESCE(words, '{}'), $1)
+         WHERE username = $2 AND NOT ($1 = ANY(COALESCE(words, '{}')))",
+        &[&word, &username],
+    ).map_err(|e| e.to_string())?;
+    Ok(updated > 0)
+}
+
+pub fn grant_emoji(pool: &Pool<PostgresConnectionManager<NoTls>>, username: &str, emoji: &str) -> Result<bool, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let updated = client.execute(
+        "UPDATE users SET emojis = array_append(COALESCE(emojis, '{}'), $1)
+         WHERE username = $2 AND NOT ($1 = ANY(COALESCE(emojis, '{}')))",
+        &[&emoji, &username],
+    ).map_err(|e| e.to_string())?;
+    Ok(updated > 0)
+}
+
+pub struct Stats {
+    pub users: i64,
+    pub total_points: i64,
+    pub quests_active: i64,
+    pub quests_done: i64,
+    pub bot_memories: i64,
+    // presence が 15 秒以内に更新されたプレイヤー
+    pub online: i64,
+    // 直近 24 時間の不正な移動
+    pub violations_24h: i64,
+}
+
+pub fn load_stats(pool: &Pool<PostgresConnectionManager<NoTls>>) -> Result<Stats, String> {
+    let mut client = pool.get().map_err(|e| e.to_string())?;
+    let row = client.query_one(
+        "SELECT
+            (SELECT count(*) FROM users),
+            (SELECT COALESCE(sum(points), 0)::BIGINT FROM users),
+            (SELECT count(*) FROM user_quests WHERE NOT done),
+            (SELECT count(*) FROM user_quests WHERE done),
+            (SELECT count(*) FROM bot_memories),
+            (SELECT count(*) FROM presence WHERE updated_at > now() - interval '15 seconds'),
+            (SELECT count(*) FROM move_audit WHERE at > now() - interval '24 hours')",
+        &[],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(Stats {
+        users: row.get(0),
+        total_points: row.get(1),
+        quests_active: row.get(2),
+        quests_done: row.get(3),
+        bot_memories: row.get(4),
+        online: row.get(5),
+        violations_24h: row.get(6),
+    })
+}
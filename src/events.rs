@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+// 【新規】プレイヤーが絵文字 (A/S/D) を出したときのイベント
+// 発信位置から BOT_HEARING_RADIUS 以内にいるボットが受け取って反応する
+#[derive(Event, Clone)]
+pub struct EmoteEvent {
+    pub emoji: String,
+    pub x: i64,
+    pub y: i64,
+}
+
+// 【新規】プレイヤーが言葉 (1-4キー) を話したときのイベント
+// 範囲の判定は EmoteEvent と同じ（半径 BOT_HEARING_RADIUS マス）
+#[derive(Event, Clone)]
+pub struct SpeakEvent {
+    pub word: String,
+    pub x: i64,
+    pub y: i64,
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// ==========================================
+// 【新規】探索済みの場所（フォグ・オブ・ウォー）
+// 世界を EXPLORE_CHUNK マス四方のチャンクに分け、チャンクごとに 1 マス 1 ビットで持つ。
+// チャンクは MAX_EXPLORED_CHUNKS 個まで。超えたら一番長く訪れていないチャンクを忘れる
+// （遠くへ何百万マス歩いても保存量は 1 人あたり最大 約 256KB）。
+// 探索したマス数 (cells) は忘れても減らない。
+// 【変更】忘れたチャンクは「何マス数えたか」だけを覚えておき、もう一度歩いてもその数を超えるまでは数えない
+// （覚えておくのは MAX_COUNTED_CHUNKS 個まで。それより昔に忘れたチャンクは、また数える）
+// ==========================================
+
+// チャンクの一辺のマス数 (32 * 32 = 1024 ビット = u64 が 16 個)
+pub const EXPLORE_CHUNK: i64 = 32;
+pub const CHUNK_WORDS: usize = (EXPLORE_CHUNK * EXPLORE_CHUNK / 64) as usize;
+
+// プレイヤーから何マス先まで「見た」ことにするか（画面に映るくらい）
+pub const EXPLORE_RADIUS: i64 = 8;
+
+// 1 人が覚えておけるチャンクの数
+pub const MAX_EXPLORED_CHUNKS: usize = 2048;
+
+// 【新規】忘れたチャンクの数え済みマス数を覚えておく数（1 つ 十数バイト）
+pub const MAX_COUNTED_CHUNKS: usize = 8192;
+
+// 実績: 探索したマス数がこれを超えたら通知する
+pub const EXPLORE_MILESTONES: [i64; 5] = [1_000, 10_000, 100_000, 1_000_000, 10_000_000];
+
+// 保存・通信用のチャンク 1 つ分
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChunkBits {
+    pub cx: i64,
+    pub cy: i64,
+    pub bits: [u64; CHUNK_WORDS],
+}
+
+// 【新規】忘れたチャンクで数え済みのマス数
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChunkCount {
+    pub cx: i64,
+    pub cy: i64,
+    pub cells: i32,
+}
+
+struct Chunk {
+    bits: [u64; CHUNK_WORDS],
+    // 最後に訪れた順番（小さいものから忘れる）
+    touched: u64,
+    // 【新規】探索済みのマス数と、cells に数え済みのマス数（一度忘れたチャンクは前者が後者に追いつくまで数えない）
+    seen: i32,
+    counted: i32,
+}
+
+impl Chunk {
+    fn new(bits: [u64; CHUNK_WORDS], touched: u64, counted: i32) -> Self {
+        let seen = bits.iter().map(|w| w.count_ones() as i32).sum::<i32>();
+        Chunk { bits, touched, seen, counted: counted.max(seen) }
+    }
+}
+
+#[derive(Default)]
+pub struct ExploredMap {
+    chunks: HashMap<(i64, i64), Chunk>,
+    // 保存していない変更があるチャンク
+    dirty: HashSet<(i64, i64)>,
+    // 【新規】忘れたチャンクの数え済みマス数と忘れた順番、そのうち保存していないもの
+    forgotten: HashMap<(i64, i64), (i32, u64)>,
+    forgotten_dirty: HashSet<(i64, i64)>,
+    // 今までに探索したマスの数
+    pub cells: i64,
+    clock: u64,
+}
+
+fn chunk_of(x: i64, y: i64) -> ((i64, i64), usize) {
+    let (cx, cy) = (x.div_euclid(EXPLORE_CHUNK), y.div_euclid(EXPLORE_CHUNK));
+    let bit = (y.rem_euclid(EXPLORE_CHUNK) * EXPLORE_CHUNK + x.rem_euclid(EXPLORE_CHUNK)) as usize;
+    ((cx, cy), bit)
+}
+
+impl ExploredMap {
+    // chunks と counted は古い順に並んでいるものとする（後ろほど最近訪れた・忘れた）
+    pub fn from_chunks(chunks: Vec<ChunkBits>, counted: Vec<ChunkCount>, cells: i64) -> Self {
+        let mut map = ExploredMap { cells, ..Default::default() };
+        for count in counted {
+            map.clock += 1;
+            map.forgotten.insert((count.cx, count.cy), (count.cells, map.clock));
+        }
+        for chunk in chunks {
+            map.clock += 1;
+            let key = (chunk.cx, chunk.cy);
+            let counted = map.forgotten.remove(&key).map_or(0, |(cells, _)| cells);
+            map.chunks.insert(key, Chunk::new(chunk.bits, map.clock, counted));
+        }
+        map.evict();
+        map
+    }
+
+    pub fn is_explored(&self, x: i64, y: i64) -> bool {
+        let (key, bit) = chunk_of(x, y);
+        self.chunks.get(&key).is_some_and(|c| c.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    // (x, y) の周り radius マスを探索済みにする。戻り値は新しく数えたマスの数
+    pub fn reveal(&mut self, x: i64, y: i64, radius: i64) -> i64 {
+        self.clock += 1;
+        let mut revealed = 0;
+        for ty in y.saturating_sub(radius)..=y.saturating_add(radius) {
+            for tx in x.saturating_sub(radius)..=x.saturating_add(radius) {
+                let (key, bit) = chunk_of(tx, ty);
+                let forgotten = &mut self.forgotten;
+                let chunk = self.chunks.entry(key).or_insert_with(|| {
+                    let counted = forgotten.remove(&key).map_or(0, |(cells, _)| cells);
+                    Chunk::new([0; CHUNK_WORDS], 0, counted)
+                });
+                chunk.touched = self.clock;
+                let mask = 1 << (bit % 64);
+                if chunk.bits[bit / 64] & mask == 0 {
+                    chunk.bits[bit / 64] |= mask;
+                    chunk.seen += 1;
+                    self.dirty.insert(key);
+                    if chunk.seen > chunk.counted {
+                        chunk.counted = chunk.seen;
+                        revealed += 1;
+                    }
+                }
+            }
+        }
+        self.cells += revealed;
+        self.evict();
+        revealed
+    }
+
+    // 上限を超えた分を、長く訪れていない順に忘れる
+    // 【変更】数え済みのマス数は残す（こちらも上限を超えたら古いものから捨てる）
+    fn evict(&mut self) {
+        while self.chunks.len() > MAX_EXPLORED_CHUNKS {
+            let Some(&oldest) = self.chunks.iter().min_by_key(|(_, c)| c.touched).map(|(k, _)| k) else { break };
+            if let Some(chunk) = self.chunks.remove(&oldest) {
+                self.clock += 1;
+                self.forgotten.insert(oldest, (chunk.counted, self.clock));
+                self.forgotten_dirty.insert(oldest);
+            }
+            self.dirty.remove(&oldest);
+        }
+        while self.forgotten.len() > MAX_COUNTED_CHUNKS {
+            let Some(&oldest) = self.forgotten.iter().min_by_key(|(_, f)| f.1).map(|(k, _)| k) else { break };
+            self.forgotten.remove(&oldest);
+            self.forgotten_dirty.remove(&oldest);
+        }
+    }
+
+    // 矩形 (x0, y0)-(x1, y1) の中の探索済みのマスをすべて呼ぶ
+    pub fn for_each_in(&self, x0: i64, y0: i64, x1: i64, y1: i64, mut f: impl FnMut(i64, i64)) {
+        for (&(cx, cy), chunk) in &self.chunks {
+            let (ox, oy) = (cx * EXPLORE_CHUNK, cy * EXPLORE_CHUNK);
+            if ox > x1 || oy > y1 || ox + EXPLORE_CHUNK <= x0 || oy + EXPLORE_CHUNK <= y0 {
+                continue;
+            }
+            for (i, &word) in chunk.bits.iter().enumerate() {
+                let mut word = word;
+                while word != 0 {
+                    let bit = i * 64 + word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    let x = ox + bit as i64 % EXPLORE_CHUNK;
+                    let y = oy + bit as i64 / EXPLORE_CHUNK;
+                    if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) {
+                        f(x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    // 保存していないチャンクを取り出す
+    pub fn take_dirty(&mut self) -> Vec<ChunkBits> {
+        let dirty: Vec<(i64, i64)> = self.dirty.drain().collect();
+        dirty.into_iter()
+            .filter_map(|key| self.chunks.get(&key).map(|c| ChunkBits { cx: key.0, cy: key.1, bits: c.bits }))
+            .collect()
+    }
+
+    // 【新規】保存していない、忘れたチャンクの数え済みマス数を取り出す
+    pub fn take_forgotten(&mut self) -> Vec<ChunkCount> {
+        let dirty: Vec<(i64, i64)> = self.forgotten_dirty.drain().collect();
+        dirty.into_iter()
+            .filter_map(|key| self.forgotten.get(&key).map(|&(cells, _)| ChunkCount { cx: key.0, cy: key.1, cells }))
+            .collect()
+    }
+}
+
+// before から after に増えたときに超えた実績（複数なら一番大きいもの）
+pub fn milestone_crossed(before: i64, after: i64) -> Option<i64> {
+    EXPLORE_MILESTONES.iter().rev().copied().find(|&m| before < m && m <= after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 負の座標を含むチャンクの境目をまたいでも 1 マスずつ数える
+    #[test]
+    fn reveal_across_chunk_boundaries() {
+        let mut map = ExploredMap::default();
+        assert_eq!(chunk_of(-1, -1), ((-1, -1), (CHUNK_WORDS * 64) - 1));
+        assert_eq!(map.reveal(0, 0, 1), 9);
+        assert_eq!(map.reveal(-1, 0, 1), 3);
+        assert_eq!(map.cells, 12);
+        assert!(map.is_explored(-2, -1) && map.is_explored(1, 1) && map.is_explored(0, -1));
+        assert!(!map.is_explored(-3, 0) && !map.is_explored(2, 0));
+
+        let mut seen = Vec::new();
+        map.for_each_in(-EXPLORE_CHUNK, -EXPLORE_CHUNK, EXPLORE_CHUNK, EXPLORE_CHUNK, |x, y| seen.push((x, y)));
+        assert_eq!(seen.len(), 12);
+        assert_eq!(map.take_dirty().len(), 4);
+    }
+
+    // 忘れたチャンクに戻っても数え直さない（保存して読み込み直しても同じ）
+    #[test]
+    fn forgotten_chunks_are_not_recounted() {
+        let mut map = ExploredMap::default();
+        let near = map.reveal(0, 0, EXPLORE_RADIUS);
+        assert_eq!(near, (EXPLORE_RADIUS * 2 + 1).pow(2));
+        let far = MAX_EXPLORED_CHUNKS as i64 + 100;
+        for i in 0..far {
+            map.reveal(i * EXPLORE_CHUNK, 100_000, 0);
+        }
+        assert!(!map.is_explored(0, 0));
+        assert_eq!(map.cells, near + far);
+
+        let forgotten = map.take_forgotten();
+        assert!(forgotten.iter().any(|c| (c.cx, c.cy) == (0, 0)));
+        let mut reloaded = ExploredMap::from_chunks(map.take_dirty(), forgotten, map.cells);
+
+        assert_eq!(map.reveal(0, 0, EXPLORE_RADIUS), 0);
+        assert_eq!(map.cells, near + far);
+        assert_eq!(reloaded.reveal(0, 0, EXPLORE_RADIUS), 0);
+        assert_eq!(reloaded.cells, near + far);
+        // 数え済みより先のマスは数える
+        assert_eq!(map.reveal(EXPLORE_RADIUS + 1, 0, 0), 1);
+    }
+}
@@ -0,0 +1,35 @@
+// Bevy のシステムは引数(Query/Res)が多くなりがちなので許可する
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+// ==========================================
+// クライアント (main.rs) とサーバー (bin/evol-server.rs) で共有するモジュール
+// 描画・ウィンドウを使うシステムは client フィーチャーでだけビルドされる
+// DB・サーバーはブラウザ版 (wasm32) ではビルドしない
+// ==========================================
+pub mod constants;
+pub mod components;
+pub mod resources;
+pub mod map;
+pub mod systems;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod database;
+pub mod content;
+pub mod events;
+pub mod quest;
+pub mod spatial;
+pub mod chat;
+pub mod explore;
+pub mod pathfind;
+pub mod stamina;
+pub mod controls;
+pub mod net;
+#[cfg(target_arch = "wasm32")]
+pub mod net_web;
+#[cfg(feature = "client")]
+pub mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod web;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod map_export;
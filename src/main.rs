@@ -1,11 +1,11 @@
-mod constants;
-mod components;
-mod resources;
-mod map;
-mod systems;
-mod database;
+// Bevy のシステムは引数(Query/Res)が多くなりがちなので許可する
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+// 【変更】モジュールはサーバー (bin/evol-server.rs) と共有するため lib.rs に移動
+use evolution_game::{constants, content, events, resources, spatial, systems};
 
 use bevy::prelude::*;
+use bevy::sprite::Material2dPlugin;
 use std::time::Duration;
 
 use resources::*;
@@ -13,33 +13,84 @@ use systems::startup::*;
 use systems::input::*;
 use systems::movement::*;
 use systems::camera::*;
+use systems::grid::*;
 use systems::ui::*;
 use systems::map_render::*;
 use systems::account::*;
 use systems::bot::*; 
-use constants::PLAYER_MOVE_INTERVAL;
+use systems::content::*;
+use systems::bot_memory::*;
+use systems::items::*;
+use systems::quest::*;
+use systems::spatial::*;
+use systems::bot_wander::*;
+use systems::net_client::*;
+use systems::minimap::*;
+use systems::explore::*;
+use systems::click_move::*;
+use systems::touch::*;
+use systems::keymap::*;
+use systems::waypoint::*;
+use systems::mute::*;
+use systems::leaderboard::*;
+use content::{ContentText, ContentTextLoader};
+use events::{EmoteEvent, SpeakEvent};
+use constants::{tick_rate_from_env, PLAYER_MOVE_INTERVAL};
+use spatial::SpatialIndex;
+use evolution_game::controls::{ActionState, Controls};
+use evolution_game::stamina::StaminaConfig;
 
 fn main() {
-    App::new()
+    // 【新規】固定ティックの回数 (Hz)
+    let tick_rate = tick_rate_from_env();
+
+    let mut app = App::new();
+    app
         .insert_resource(ClearColor(Color::WHITE))
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Evolution: Origin".into(),
                 resolution: (800., 600.).into(),
+                // 【新規】ブラウザ版は web/index.html の <canvas id="game"> に描く
+                #[cfg(target_arch = "wasm32")]
+                canvas: Some("#game".into()),
+                #[cfg(target_arch = "wasm32")]
+                fit_canvas_to_parent: true,
                 ..default()
             }),
             ..default()
+        }).set(AssetPlugin {
+            // assets/ 以下の変更を監視してホットリロードする
+            // 【変更】ファイル監視はデスクトップ版だけ（ブラウザ版はファイルを見られない）
+            watch_for_changes_override: Some(cfg!(feature = "desktop")),
+            ..default()
         }))
+        .add_plugins(Material2dPlugin::<GridMaterial>::default())
+        .init_asset::<ContentText>()
+        .init_asset_loader::<ContentTextLoader>()
         .init_state::<GameState>()
+        .insert_resource(Time::<Fixed>::from_hz(tick_rate))
         
         .insert_resource(MoveTimer::new(Timer::new(Duration::from_secs_f32(PLAYER_MOVE_INTERVAL), TimerMode::Repeating)))
         
         .insert_resource(InputBuffer(Vec2::ZERO))
+        .insert_resource(StaminaConfig::from_env())
+        .init_resource::<MovePath>()
+        .init_resource::<TouchState>()
+        .init_resource::<Controls>()
+        .init_resource::<ActionState>()
+        .init_resource::<ControlsMenu>()
+        .init_resource::<WaypointMenu>()
+        .init_resource::<MuteList>()
+        .init_resource::<Leaderboard>()
+        .init_resource::<CameraZoom>()
         .insert_resource(ChatLog { messages: Vec::new() })
+        .init_resource::<ChatRateLimit>()
         
         .insert_resource(EmojiConfig {
             s_key: "😁".to_string(),
             d_key: "😭".to_string(),
+            unlocked: Vec::new(),
         })
         .insert_resource(EmojiSelectState {
             is_open: false,
@@ -69,38 +120,156 @@ fn main() {
         
         // 【新規】BotDialoguesを初期化
         .insert_resource(BotDialogues::default())
+        .init_resource::<EmojiCatalog>()
+        .init_resource::<ItemTable>()
+        .init_resource::<QuestLog>()
+        .init_resource::<TakenItems>()
+        .init_resource::<SpatialIndex>()
+        .init_resource::<Waypoints>()
+        .init_resource::<MapState>()
+        .init_resource::<Explored>()
+        .insert_resource(ExploreSaveTimer(Timer::from_seconds(10.0, TimerMode::Repeating)))
+        .add_event::<EmoteEvent>()
+        .add_event::<SpeakEvent>()
+        .insert_resource(BotMemorySaveTimer(Timer::from_seconds(5.0, TimerMode::Repeating)))
         
         .add_systems(Startup, setup)
+        // コンテンツの再読み込みはログイン画面でも反映する
+        .add_systems(Update, apply_content_updates)
         
         .add_systems(OnEnter(GameState::Login), setup_account_ui)
         .add_systems(Update, handle_account_input.run_if(in_state(GameState::Login)))
         .add_systems(OnExit(GameState::Login), cleanup_account_ui)
 
-        .add_systems(OnEnter(GameState::Playing), setup_game)
+        .add_systems(OnEnter(GameState::Playing), (setup_game, setup_quest_log, setup_minimap, setup_explored, setup_touch_controls, setup_controls, setup_waypoints, setup_grid, setup_mute_list, setup_taken_items, setup_leaderboard))
 
         .add_systems(Update, (
             handle_movement_input,
             handle_chat_input,
+            handle_mute_input,
             
-            move_player_tick,
             sync_player_pixel_pos,
             
-            camera_follow,
-            draw_grid_optimized,
+            // 【変更】ズームの入力を受けてから、なめらかに追いかける
+            handle_camera_zoom,
+            camera_follow.after(sync_player_pixel_pos).after(handle_camera_zoom),
+            // 【変更】グリッドはシェーダーで描く（カメラが動いた後に合わせる）
+            update_grid.after(camera_follow),
             spawn_visible_obstacles,
             
             spawn_visible_bots,
             despawn_far_bots,
-            bot_wander_system,
-            update_bot_chat,
+            bot_react_to_emotes.after(handle_chat_input),
+            bot_respond_to_words.after(handle_chat_input),
             
             update_ui,
-            update_chat_ui, 
+            update_stamina_bar,
             update_chat_menu_ui,
             update_emoji_select_menu,
-            update_notification_ui,
             handle_save_button_interaction,
         ).run_if(in_state(GameState::Playing)))
+
+        // 【新規】ゲームの処理（移動・ボット・チャットのタイマー）は固定ティックで動かす
+        // FixedUpdate は毎フレーム Update より前に（必要な回数だけ）動くので、
+        // Update のシステムはいつも移動の後の位置を見る
+        .add_systems(FixedUpdate, (
+            move_player_tick,
+            step_pixel_motion.after(move_player_tick),
+            // 【変更】サーバー接続中はボットの位置をサーバーから受け取る
+            bot_wander_system.run_if(not(resource_exists::<NetClient>)),
+            update_bot_chat,
+            update_chat_ui,
+            update_notification_ui,
+        ).run_if(in_state(GameState::Playing)))
         
-        .run();
-}
\ No newline at end of file
+        // 【新規】ボットの記憶の読み込み・書き込み
+        .add_systems(Update, (
+            load_bot_memories,
+            save_bot_memories,
+        ).run_if(in_state(GameState::Playing)))
+        // 【新規】終了するときに未保存の記憶を書き込む
+        .add_systems(Last, flush_bot_memories_on_exit.run_if(resource_exists::<evolution_game::storage::Storage>))
+
+        // 【新規】アイテムとクエスト
+        .add_systems(Update, (
+            spawn_visible_items,
+            despawn_far_items,
+            pickup_items,
+            handle_quest_speech.after(bot_respond_to_words),
+            check_visit_quests,
+            update_quest_log_ui,
+        ).run_if(in_state(GameState::Playing)))
+
+        // 【新規】操作の割り当て（ログイン画面でも使うので、状態によらず入力をまとめる）
+        .add_systems(PreUpdate, update_action_state.after(bevy::ui::UiSystem::Focus))
+        .add_systems(PreUpdate, handle_controls_menu.after(update_action_state).run_if(in_state(GameState::Playing)))
+        .add_systems(Update, update_controls_menu_ui.run_if(in_state(GameState::Playing)))
+
+        // 【新規】タッチ操作（ボタンを操作に変えるので、ActionState の更新より前に動かす）
+        .add_systems(PreUpdate, (
+            detect_touch,
+            apply_touch_buttons,
+        ).chain().after(bevy::ui::UiSystem::Focus).before(update_action_state).run_if(in_state(GameState::Playing)))
+        .add_systems(Update, (
+            update_touch_labels,
+            layout_touch_controls,
+        ).run_if(in_state(GameState::Playing)))
+
+        // 【新規】クリック / タップ移動（キー入力の後に判定し、次の移動で 1 歩目を使う）
+        .add_systems(Update, (
+            handle_click_to_move.after(handle_movement_input),
+            draw_move_path,
+        ).run_if(in_state(GameState::Playing)))
+
+        // 【新規】ミニマップとワールドマップ
+        // 【変更】探索済みの場所を記録してから描く
+        // 【変更】ワールドマップ（とウェイポイントの一覧）は開いている間ほかの入力を止めるので PreUpdate で動かす
+        // 【変更】ミュートの一覧・ランキングも開いている間ほかの入力を止める
+        .add_systems(PreUpdate, (
+            handle_waypoint_input,
+            handle_world_map_input,
+            handle_mute_list_input,
+            handle_leaderboard_input,
+        ).chain().after(handle_controls_menu).run_if(in_state(GameState::Playing)))
+        .add_systems(Update, (
+            explore_around_player,
+            save_explored,
+            update_minimap.after(explore_around_player),
+            update_world_map.after(explore_around_player),
+            notify_teleport_stone,
+            update_waypoint_ui,
+            update_mute_list_ui,
+            update_leaderboard_ui,
+        ).run_if(in_state(GameState::Playing)))
+
+        // 【新規】空間インデックス（話しかけ・ボットの移動より先に更新する）
+        .add_systems(Update, (
+            update_spatial_index
+                .before(bot_react_to_emotes)
+                .before(bot_respond_to_words),
+            sync_bot_pixel_pos,
+        ).run_if(in_state(GameState::Playing)))
+
+        // 【新規】ネットワーク対戦（GAME_SERVER が設定されているとき・ブラウザ版で動く）
+        // 【変更】setup_net_client は保存先 (Storage) も決める。ログインの返事を受け取るので受信は常に動かす
+        .add_systems(Startup, setup_net_client)
+        .add_systems(Update, receive_server_messages.before(update_spatial_index).run_if(resource_exists::<NetClient>))
+        .add_systems(Update, send_player_actions.after(handle_chat_input)
+            .run_if(in_state(GameState::Playing)).run_if(resource_exists::<NetClient>))
+        .add_systems(Update, sync_remote_player_pixel_pos.run_if(in_state(GameState::Playing)));
+
+    // 【新規】プレゼンス（サーバーなしで同じ DB の他のプレイヤーを見る）
+    #[cfg(not(target_arch = "wasm32"))]
+    app.insert_resource(PresenceTimer(Timer::from_seconds(1.0, TimerMode::Repeating)))
+        .add_systems(Update, systems::presence::sync_presence
+            .after(handle_chat_input)
+            .before(update_spatial_index)
+            .run_if(in_state(GameState::Playing)));
+
+    // 【新規】ブラウザ版はソケットのスレッドがないので、フレームの最後にまとめて送る
+    #[cfg(target_arch = "wasm32")]
+    // 終了時に書き込んだボットの記憶も同じフレームで送る
+    app.add_systems(Last, flush_web_socket.after(flush_bot_memories_on_exit).run_if(resource_exists::<NetClient>));
+
+    app.run();
+}
@@ -1,5 +1,6 @@
 // 障害物の出現ロジック
-const OBSTACLE_DENSITY: u64 = 3; 
+// 【変更】マップの書き出し (map_export) で期待値と比べるので公開する
+pub const OBSTACLE_DENSITY: u64 = 3; 
 
 pub fn is_obstacle(x: i64, y: i64) -> bool {
     if x == 0 && y == 0 { return false; }
@@ -13,15 +14,24 @@ pub fn is_obstacle(x: i64, y: i64) -> bool {
     (h % 100) < OBSTACLE_DENSITY as u128
 }
 
+// 【新規】ボット固有の値（性格など）を決めるためのシード
+// 同じスポーン地点のボットは毎回同じ値になる
+pub fn bot_seed(x: i64, y: i64) -> u64 {
+    let mut h = (x as u64).wrapping_mul(0xD6E8FEB86659FD93);
+    h = (h ^ (y as u64)).wrapping_mul(0x9E3779B97F4A7C15);
+    h = (h ^ (h >> 32)).wrapping_mul(0xD6E8FEB86659FD93);
+    h ^ (h >> 32)
+}
+
 // 【修正】ボットの出現ロジック
 // 2億体目標とのことですが、まずは「見つかること」を優先し
 // 200セルに1体 (0.5%) 程度の密度にします。
-const BOT_DENSITY: u64 = 1; 
-const BOT_MODULO: u64 = 200; // ここを小さくすると密度が上がる
+pub const BOT_DENSITY: u64 = 1; 
+pub const BOT_MODULO: u64 = 200; // ここを小さくすると密度が上がる
 
 pub fn is_bot_spawn(x: i64, y: i64) -> bool {
     // スタート地点付近はボットなし
-    if x.abs() < 10 && y.abs() < 10 { return false; }
+    if x.unsigned_abs() < 10 && y.unsigned_abs() < 10 { return false; }
 
     if is_obstacle(x, y) { return false; }
 
@@ -31,4 +41,105 @@ pub fn is_bot_spawn(x: i64, y: i64) -> bool {
     h = h ^ (h >> 31);
     
     (h % BOT_MODULO) < BOT_DENSITY
-}
\ No newline at end of file
+}
+
+// 【新規】アイテムの出現ロジック
+// 150セルに1個程度。障害物・ボットのマスと原点付近には置かない
+pub const ITEM_MODULO: u64 = 150;
+
+fn item_hash(x: i64, y: i64) -> u64 {
+    let mut h = (y as u64).wrapping_mul(0x94D049BB133111EB);
+    h = (h ^ (x as u64)).wrapping_mul(0xBF58476D1CE4E5B9);
+    h = (h ^ (h >> 29)).wrapping_mul(0x9E3779B97F4A7C15);
+    h ^ (h >> 32)
+}
+
+pub fn is_item_spawn(x: i64, y: i64) -> bool {
+    if x.unsigned_abs() < 3 && y.unsigned_abs() < 3 { return false; }
+    if is_obstacle(x, y) || is_bot_spawn(x, y) { return false; }
+
+    item_hash(x, y).is_multiple_of(ITEM_MODULO)
+}
+
+// そのマスに落ちているアイテムの種類 (アイテム表のインデックス)
+pub fn item_index(x: i64, y: i64, item_count: usize) -> usize {
+    if item_count == 0 { return 0; }
+    ((item_hash(x, y) / ITEM_MODULO) % item_count as u64) as usize
+}
+
+// 【新規】テレポート石の出現ロジック
+// 4000セルに1個程度のまれな石。上に立つと保存した地点 (ウェイポイント) へ飛べる。
+// 障害物・ボット・アイテムのマスには置かない
+pub const TELEPORT_MODULO: u64 = 4000;
+
+pub fn is_teleport_stone(x: i64, y: i64) -> bool {
+    if is_obstacle(x, y) || is_bot_spawn(x, y) || is_item_spawn(x, y) { return false; }
+
+    let mut h = (x as u64).wrapping_mul(0xBF58476D1CE4E5B9);
+    h = (h ^ (y as u64).wrapping_mul(0xD6E8FEB86659FD93)).wrapping_mul(0x9E3779B97F4A7C15);
+    h = (h ^ (h >> 31)).wrapping_mul(0x94D049BB133111EB);
+    h ^= h >> 29;
+
+    h.is_multiple_of(TELEPORT_MODULO)
+}
+
+// 【新規】マスの種類（マップの書き出し・ミニマップ用）
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tile {
+    Empty,
+    Obstacle,
+    BotSpawn,
+    Item,
+    TeleportStone,
+}
+
+impl Tile {
+    pub const ALL: [Tile; 5] = [Tile::Obstacle, Tile::BotSpawn, Tile::Item, Tile::TeleportStone, Tile::Empty];
+
+    // # = 障害物, B = ボットの出現地点, * = アイテム, T = テレポート石, . = 何もない
+    pub fn ascii(self) -> char {
+        match self {
+            Tile::Empty => '.',
+            Tile::Obstacle => '#',
+            Tile::BotSpawn => 'B',
+            Tile::Item => '*',
+            Tile::TeleportStone => 'T',
+        }
+    }
+
+    // 画面の色に合わせる（障害物は灰色、ボットは青、アイテムは金色、テレポート石は青緑）
+    pub fn rgb(self) -> [u8; 3] {
+        match self {
+            Tile::Empty => [245, 245, 245],
+            Tile::Obstacle => [110, 110, 110],
+            Tile::BotSpawn => [0, 0, 255],
+            Tile::Item => [204, 153, 0],
+            Tile::TeleportStone => [0, 170, 170],
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tile::Empty => "EMPTY",
+            Tile::Obstacle => "OBSTACLE",
+            Tile::BotSpawn => "BOT",
+            Tile::Item => "ITEM",
+            Tile::TeleportStone => "TELEPORT",
+        }
+    }
+}
+
+// 生成関数を優先順に見て、そのマスに何があるかを返す
+pub fn tile_at(x: i64, y: i64) -> Tile {
+    if is_obstacle(x, y) {
+        Tile::Obstacle
+    } else if is_bot_spawn(x, y) {
+        Tile::BotSpawn
+    } else if is_item_spawn(x, y) {
+        Tile::Item
+    } else if is_teleport_stone(x, y) {
+        Tile::TeleportStone
+    } else {
+        Tile::Empty
+    }
+}
@@ -0,0 +1,283 @@
+use crate::map::{Tile, BOT_DENSITY, BOT_MODULO, ITEM_MODULO, OBSTACLE_DENSITY, TELEPORT_MODULO};
+use std::fs::File;
+use std::io::BufWriter;
+
+// ==========================================
+// 【新規】ワールドの書き出し（歩かずに生成結果を確かめる）
+// 矩形の範囲を step マスおきに map::tile_at で調べ、
+// 凡例付きの ASCII か PNG にする。密度と偏り（隣り合う確率）も数える。
+// 座標は i64 の全範囲で使える（巨大な範囲は step を大きくして間引く）
+// ==========================================
+
+// 調べる範囲（両端を含む）。上の行が +y（ゲーム画面と同じ向き）
+#[derive(Clone, Copy, Debug)]
+pub struct Region {
+    pub x0: i64,
+    pub y0: i64,
+    pub x1: i64,
+    pub y1: i64,
+    pub step: i64,
+}
+
+impl Region {
+    // 角の順番はどちらでもよい
+    pub fn new(x0: i64, y0: i64, x1: i64, y1: i64, step: i64) -> Self {
+        Self { x0: x0.min(x1), y0: y0.min(y1), x1: x0.max(x1), y1: y0.max(y1), step: step.max(1) }
+    }
+
+    // 端から端までが i64 に収まらない範囲もあるので i128 で計算する
+    pub fn width(&self) -> i64 {
+        span(self.x0, self.x1, self.step)
+    }
+
+    pub fn height(&self) -> i64 {
+        span(self.y0, self.y1, self.step)
+    }
+
+    // 調べるマスの数（オーバーフローしない）
+    pub fn samples(&self) -> i64 {
+        self.width().saturating_mul(self.height())
+    }
+}
+
+fn span(from: i64, to: i64, step: i64) -> i64 {
+    ((to as i128 - from as i128) / step as i128 + 1).min(i64::MAX as i128) as i64
+}
+
+// 調べた結果。tiles は上の行から順に width × height
+pub struct Sample {
+    pub region: Region,
+    pub tiles: Vec<Tile>,
+    pub counts: [u64; Tile::ALL.len()],
+    // 種類ごとの「右か上の隣が同じ種類」の回数（偏りの確認用）
+    pub same_neighbors: [u64; Tile::ALL.len()],
+    pub neighbors: [u64; Tile::ALL.len()],
+}
+
+fn tile_index(tile: Tile) -> usize {
+    Tile::ALL.iter().position(|&t| t == tile).unwrap_or(0)
+}
+
+pub fn sample(region: Region) -> Sample {
+    let (width, height) = (region.width() as usize, region.height() as usize);
+    let mut tiles = Vec::with_capacity(width * height);
+    for row in 0..height as i64 {
+        let y = (region.y1 as i128 - row as i128 * region.step as i128) as i64;
+        for col in 0..width as i64 {
+            let x = (region.x0 as i128 + col as i128 * region.step as i128) as i64;
+            tiles.push(crate::map::tile_at(x, y));
+        }
+    }
+
+    let mut counts = [0; Tile::ALL.len()];
+    let mut same_neighbors = [0; Tile::ALL.len()];
+    let mut neighbors = [0; Tile::ALL.len()];
+    for row in 0..height {
+        for col in 0..width {
+            let tile = tiles[row * width + col];
+            let i = tile_index(tile);
+            counts[i] += 1;
+            let right = (col + 1 < width).then(|| tiles[row * width + col + 1]);
+            let up = (row > 0).then(|| tiles[(row - 1) * width + col]);
+            for other in [right, up].into_iter().flatten() {
+                neighbors[i] += 1;
+                if other == tile {
+                    same_neighbors[i] += 1;
+                }
+            }
+        }
+    }
+
+    Sample { region, tiles, counts, same_neighbors, neighbors }
+}
+
+// 生成ロジックから計算した割合（優先順: 障害物 → ボット → アイテム → テレポート石）
+pub fn expected_ratio(tile: Tile) -> f64 {
+    let obstacle = OBSTACLE_DENSITY as f64 / 100.0;
+    let bot = (1.0 - obstacle) * BOT_DENSITY as f64 / BOT_MODULO as f64;
+    let item = (1.0 - obstacle - bot) / ITEM_MODULO as f64;
+    let stone = (1.0 - obstacle - bot - item) / TELEPORT_MODULO as f64;
+    match tile {
+        Tile::Obstacle => obstacle,
+        Tile::BotSpawn => bot,
+        Tile::Item => item,
+        Tile::TeleportStone => stone,
+        Tile::Empty => 1.0 - obstacle - bot - item - stone,
+    }
+}
+
+// 1 種類ぶんの集計
+pub struct TileStats {
+    pub tile: Tile,
+    pub count: u64,
+    pub ratio: f64,
+    pub expected: f64,
+    // 隣も同じ種類になる確率 ÷ その種類の割合。ハッシュに偏りがなければ 1 前後
+    // (step = 1 のときだけ意味がある。原点付近は出現しないので少しずれる)
+    pub clustering: Option<f64>,
+}
+
+impl Sample {
+    pub fn stats(&self) -> Vec<TileStats> {
+        let total = self.tiles.len().max(1) as f64;
+        Tile::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, &tile)| {
+                let ratio = self.counts[i] as f64 / total;
+                let clustering = (self.neighbors[i] > 0 && ratio > 0.0)
+                    .then(|| self.same_neighbors[i] as f64 / self.neighbors[i] as f64 / ratio);
+                TileStats { tile, count: self.counts[i], ratio, expected: expected_ratio(tile), clustering }
+            })
+            .collect()
+    }
+
+    pub fn legend(&self) -> Vec<String> {
+        self.stats()
+            .iter()
+            .map(|s| format!("{} {} {:.2}%", s.tile.ascii(), s.tile.name(), s.ratio * 100.0))
+            .collect()
+    }
+
+    // 凡例付きの ASCII
+    pub fn to_ascii(&self) -> String {
+        let width = self.region.width() as usize;
+        let mut out = String::with_capacity(self.tiles.len() + self.tiles.len() / width.max(1) + 256);
+        for row in self.tiles.chunks(width.max(1)) {
+            out.extend(row.iter().map(|t| t.ascii()));
+            out.push('\n');
+        }
+        let r = &self.region;
+        out += &format!("\n({}, {}) - ({}, {}) step {}\n", r.x0, r.y0, r.x1, r.y1, r.step);
+        for line in self.legend() {
+            out += &line;
+            out.push('\n');
+        }
+        out
+    }
+
+    // 1 マス scale ピクセルの PNG。下に凡例を付ける。原点が範囲内なら赤い点を打つ
+    pub fn write_png(&self, path: &str, scale: u32) -> Result<(), String> {
+        let scale = scale.max(1) as usize;
+        let (cols, rows) = (self.region.width() as usize, self.region.height() as usize);
+        let legend = self.legend();
+        let legend_width = legend.iter().map(|l| l.len()).max().unwrap_or(0) * GLYPH_ADVANCE + LEGEND_SWATCH + PADDING * 3;
+        let width = (cols * scale).max(legend_width);
+        let height = rows * scale + PADDING + legend.len() * LEGEND_LINE + PADDING;
+        if width.saturating_mul(height) > MAX_PNG_PIXELS {
+            return Err(format!("image would be {}x{} (max {} pixels); use a larger --step", width, height, MAX_PNG_PIXELS));
+        }
+
+        let mut canvas = Canvas { width, pixels: vec![255; width * height * 3] };
+        for (i, &tile) in self.tiles.iter().enumerate() {
+            canvas.fill((i % cols) * scale, (i / cols) * scale, scale, scale, tile.rgb());
+        }
+        let r = &self.region;
+        if r.step == 1 && (r.x0..=r.x1).contains(&0) && (r.y0..=r.y1).contains(&0) {
+            canvas.fill((-r.x0) as usize * scale, (r.y1 as usize) * scale, scale, scale, [220, 0, 0]);
+        }
+
+        let top = rows * scale + PADDING;
+        for (i, (line, tile)) in legend.iter().zip(Tile::ALL).enumerate() {
+            let y = top + i * LEGEND_LINE;
+            canvas.fill(PADDING, y, LEGEND_SWATCH, LEGEND_SWATCH, tile.rgb());
+            // 先頭の ASCII 記号は色の見本と重複するので省く
+            canvas.text(PADDING * 2 + LEGEND_SWATCH, y + 1, &line[2..]);
+        }
+
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(&canvas.pixels).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+const MAX_PNG_PIXELS: usize = 64 * 1024 * 1024;
+const PADDING: usize = 8;
+const LEGEND_SWATCH: usize = 12;
+const LEGEND_LINE: usize = 16;
+// 3x5 のドットフォントを 2 倍で描く
+const GLYPH_SCALE: usize = 2;
+const GLYPH_ADVANCE: usize = 4 * GLYPH_SCALE;
+
+struct Canvas {
+    width: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, color: [u8; 3]) {
+        let height = self.pixels.len() / 3 / self.width;
+        for py in y..(y + h).min(height) {
+            for px in x..(x + w).min(self.width) {
+                let i = (py * self.width + px) * 3;
+                self.pixels[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    fn text(&mut self, x: usize, y: usize, text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            let rows = glyph(c);
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) != 0 {
+                        let px = x + i * GLYPH_ADVANCE + col * GLYPH_SCALE;
+                        let py = y + row * GLYPH_SCALE;
+                        self.fill(px, py, GLYPH_SCALE, GLYPH_SCALE, [0, 0, 0]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 凡例に使う文字だけ（それ以外は空白）
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => [0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => [0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b110],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        _ => [0; 5],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 種類ごとの配列が Tile::ALL と同じ長さか（種類を増やしたときの添字あふれ）
+    #[test]
+    fn sample_counts_every_tile_kind() {
+        let sample = sample(Region::new(-10, -10, 10, 10, 1));
+        assert_eq!(sample.tiles.len(), 21 * 21);
+        assert_eq!(sample.counts.iter().sum::<u64>(), 21 * 21);
+        assert_eq!(sample.stats().len(), Tile::ALL.len());
+        assert!(sample.to_ascii().contains("EMPTY"));
+    }
+}
@@ -0,0 +1,284 @@
+use serde::{Deserialize, Serialize};
+use crate::explore::{ChunkBits, ChunkCount};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::{TcpListener, TcpStream};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use tungstenite::stream::MaybeTlsStream;
+#[cfg(not(target_arch = "wasm32"))]
+use tungstenite::{Message, WebSocket};
+
+// ==========================================
+// ネットワーク対戦
+// サーバー (evol-server) がマップ・ボット・移動判定を持ち、
+// クライアントは入力を送ってスナップショットを受け取る。
+// 通信は WebSocket のテキストフレームに JSON を載せる。
+// ソケットは専用スレッドで読み書きし、Bevy 側とは mpsc チャンネルでやり取りする
+// (ブラウザ版はスレッドが使えないので net_web.rs がブラウザの WebSocket で同じことをする)
+// サーバーにつないでいるクライアントは、ログインやセーブも DB ではなくサーバー経由で行う
+// ==========================================
+
+pub type ClientId = u64;
+
+// サーバーの待ち受けアドレス (環境変数 SERVER_BIND で変更可)
+pub const DEFAULT_BIND: &str = "0.0.0.0:9001";
+pub const DEFAULT_PORT: u16 = 9001;
+
+// クライアント → サーバー
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    // 【変更】最初に送る。成功すれば LoggedIn が返り、サーバー上にプレイヤーが出る
+    Login { username: String, password: String },
+    Register { username: String, password: String },
+    // 隣のマスへの移動（サーバーが判定し、ダメなら Correction が返る）
+    Move { x: i64, y: i64 },
+    Emote { emoji: String },
+    Say { word: String },
+    // 【新規】セーブ類（ユーザー名はログインしたものを使う。位置はサーバーのものを保存する）
+    // 【変更】言葉・持ち物・ポイントはサーバーが DB に書くので、クライアントからは送らない
+    SaveProgress { s_key: String, d_key: String },
+    AcceptQuest { giver_x: i64, giver_y: i64 },
+    // 【変更】達成の判定と報酬はサーバーが保存したクエストから決める
+    CompleteQuest { giver_x: i64, giver_y: i64 },
+    LoadBotMemories { bot_x: i64, bot_y: i64 },
+    SaveBotMemory { bot_x: i64, bot_y: i64, times_greeted: i32, last_seen: i64, affinity: i32 },
+    // 【新規】読み込む前のボットの記憶（DB の内容に足し合わせる）
+    AddBotMemory { bot_x: i64, bot_y: i64, times_greeted: i32, last_seen: i64, affinity: i32 },
+    // 【新規】target の言葉を受け取らない / 受け取る
+    SetMuted { target: String, muted: bool },
+    // 【新規】操作の割り当て
+    SaveBindings { bindings: String },
+    // 【新規】ウェイポイント。追加はサーバーの今の位置に置く（同じ名前なら置き直す）
+    AddWaypoint { name: String },
+    DeleteWaypoint { name: String },
+    // テレポート石の上にいればウェイポイントへ飛ぶ（行き先は Correction で返る）
+    Teleport { name: String },
+    // 【新規】探索マス数のランキングを問い合わせる
+    LoadLeaderboard,
+}
+
+// サーバー → クライアント
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ServerMessage {
+    // 【変更】Login / Register の結果
+    LoggedIn { id: ClientId, profile: Box<UserProfile> },
+    LoginFailed { reason: String },
+    // 移動が認められなかったときの正しい位置
+    Correction { x: i64, y: i64 },
+    // 周囲のプレイヤーとボット（受け取った側は前回との差分を自分で取る）
+    Snapshot { players: Vec<PlayerState>, bots: Vec<BotState> },
+    Emote { id: ClientId, emoji: String },
+    Chat { id: ClientId, name: String, word: String },
+    // LoadBotMemories の返事 (username, times_greeted, last_seen, affinity)
+    BotMemories { bot_x: i64, bot_y: i64, rows: Vec<(String, i32, i64, i32)> },
+    // 【新規】AddWaypoint の結果。置いた位置はサーバーの位置（クライアントの位置とずれていれば直す）
+    WaypointSaved { name: String, x: i64, y: i64 },
+    WaypointRejected { name: String, reason: String },
+    // 【新規】Teleport できなかった理由（できたときは Correction が届く）
+    TeleportFailed { reason: String },
+    // 【新規】LoadLeaderboard の返事 (username, explored_cells, points)
+    Leaderboard { rows: Vec<(String, i64, i32)> },
+}
+
+// 【新規】ログイン時に渡すセーブデータ一式
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserProfile {
+    pub x: i64,
+    pub y: i64,
+    pub words: Vec<String>,
+    pub s_key: String,
+    pub d_key: String,
+    pub items: Vec<String>,
+    pub points: i32,
+    pub emojis: Vec<String>,
+    // 【変更】受けたときの内容も渡す
+    pub quests: Vec<crate::quest::SavedQuest>,
+    // 【新規】ミュートしているユーザー名
+    pub muted: Vec<String>,
+    // 【新規】探索済みのチャンク（古い順）と探索マス数
+    pub explored: Vec<ChunkBits>,
+    // 【新規】忘れたチャンクの数え済みマス数（忘れた順）
+    pub explored_counted: Vec<ChunkCount>,
+    pub explored_cells: i64,
+    // 【新規】操作の割り当て (JSON。空なら既定)
+    pub key_bindings: String,
+    // 【新規】ウェイポイント（作った順）
+    pub waypoints: Vec<crate::resources::Waypoint>,
+    // 【新規】拾ったアイテムのマス
+    pub taken_items: Vec<(i64, i64)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlayerState {
+    pub id: ClientId,
+    pub name: String,
+    pub x: i64,
+    pub y: i64,
+}
+
+// ボットはスポーン地点で識別する（クライアントも同じ地点にボットを出している）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BotState {
+    pub spawn_x: i64,
+    pub spawn_y: i64,
+    pub x: i64,
+    pub y: i64,
+}
+
+// サーバーのソケットスレッドから Bevy 側に届くもの
+#[cfg(not(target_arch = "wasm32"))]
+pub enum ServerEvent {
+    Connected(ClientId, Sender<ServerMessage>),
+    Message(ClientId, ClientMessage),
+    Disconnected(ClientId),
+}
+
+// 読み込むものがないときの待ち時間
+#[cfg(not(target_arch = "wasm32"))]
+const IDLE_SLEEP: Duration = Duration::from_millis(5);
+
+// 待ち受けを開始する。接続ごとにスレッドを1本立てる
+#[cfg(not(target_arch = "wasm32"))]
+pub fn start_server(addr: &str) -> io::Result<Receiver<ServerEvent>> {
+    let listener = TcpListener::bind(addr)?;
+    let (event_tx, event_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut next_id: ClientId = 1;
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let id = next_id;
+            next_id += 1;
+            let event_tx = event_tx.clone();
+            thread::spawn(move || serve_client(id, stream, event_tx));
+        }
+    });
+
+    Ok(event_rx)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn serve_client(id: ClientId, stream: TcpStream, event_tx: Sender<ServerEvent>) {
+    let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            eprintln!("Handshake Error ({}): {}", peer, e);
+            return;
+        }
+    };
+    if socket.get_mut().set_nonblocking(true).is_err() {
+        return;
+    }
+
+    let (out_tx, out_rx) = mpsc::channel::<ServerMessage>();
+    if event_tx.send(ServerEvent::Connected(id, out_tx)).is_err() {
+        return;
+    }
+    println!("Client {} connected from {}", id, peer);
+
+    pump(&mut socket, &out_rx, |message: ClientMessage| {
+        event_tx.send(ServerEvent::Message(id, message)).is_ok()
+    });
+
+    println!("Client {} disconnected", id);
+    let _ = event_tx.send(ServerEvent::Disconnected(id));
+}
+
+// サーバーに接続する。戻り値は (送信用, 受信用) のチャンネル
+#[cfg(not(target_arch = "wasm32"))]
+pub fn connect(url: &str) -> Result<(Sender<ClientMessage>, Receiver<ServerMessage>), String> {
+    let (mut socket, _) = tungstenite::connect(url).map_err(|e| e.to_string())?;
+    match socket.get_mut() {
+        MaybeTlsStream::Plain(stream) => stream.set_nonblocking(true).map_err(|e| e.to_string())?,
+        _ => return Err("only ws:// is supported".to_string()),
+    }
+
+    let (out_tx, out_rx) = mpsc::channel::<ClientMessage>();
+    let (in_tx, in_rx) = mpsc::channel::<ServerMessage>();
+
+    thread::spawn(move || {
+        pump(&mut socket, &out_rx, |message: ServerMessage| in_tx.send(message).is_ok());
+        eprintln!("Disconnected from server");
+    });
+
+    Ok((out_tx, in_rx))
+}
+
+// ソケットの読み書きループ（サーバー・クライアント共通）
+// 届いたメッセージは on_message に渡し、outgoing に積まれたものを送る。
+// 接続が切れるか、どちらかのチャンネルが閉じたら終わる
+#[cfg(not(target_arch = "wasm32"))]
+fn pump<S, In, Out>(
+    socket: &mut WebSocket<S>,
+    outgoing: &Receiver<Out>,
+    mut on_message: impl FnMut(In) -> bool,
+) where
+    S: io::Read + io::Write,
+    In: for<'de> Deserialize<'de>,
+    Out: Serialize,
+{
+    loop {
+        let mut busy = false;
+
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                busy = true;
+                match serde_json::from_str::<In>(&text) {
+                    Ok(message) => {
+                        if !on_message(message) {
+                            break;
+                        }
+                    }
+                    // 壊れたメッセージは無視する（切断はしない）
+                    Err(e) => eprintln!("Bad Message: {}", e),
+                }
+            }
+            Ok(Message::Close(_)) => break,
+            Ok(_) => busy = true,
+            Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(_) => break,
+        }
+
+        loop {
+            match outgoing.try_recv() {
+                Ok(message) => {
+                    busy = true;
+                    let Ok(text) = serde_json::to_string(&message) else { continue };
+                    // ノンブロッキングなので WouldBlock は「送信待ちに積んだ」という意味
+                    match socket.write(Message::Text(text)) {
+                        Ok(()) => {}
+                        Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {}
+                        Err(_) => return,
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                // Bevy 側が接続を手放した（キックなど）
+                Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    let _ = socket.flush();
+                    return;
+                }
+            }
+        }
+
+        match socket.flush() {
+            Ok(()) => {}
+            Err(tungstenite::Error::Io(e)) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(_) => break,
+        }
+
+        if !busy {
+            thread::sleep(IDLE_SLEEP);
+        }
+    }
+}
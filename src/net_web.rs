@@ -0,0 +1,82 @@
+use crate::net::{ClientMessage, ServerMessage, DEFAULT_PORT};
+use std::cell::RefCell;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use wasm_bindgen::prelude::*;
+use web_sys::{MessageEvent, WebSocket};
+
+// ==========================================
+// 【新規】ブラウザ版の WebSocket
+// ブラウザではスレッドが使えないので、受信はブラウザのコールバックで、
+// 送信は毎フレーム flush_outgoing で行う。Bevy 側から見た形は net::connect と同じ
+// ==========================================
+
+thread_local! {
+    // WebSocket は Send でないので Bevy のリソースには入れられない
+    static SOCKET: RefCell<Option<(WebSocket, Receiver<ClientMessage>)>> = const { RefCell::new(None) };
+}
+
+// ページを配信したホストの DEFAULT_PORT につなぐ
+pub fn default_server_url() -> Option<String> {
+    let location = web_sys::window()?.location();
+    let host = location.hostname().ok()?;
+    let scheme = if location.protocol().ok()? == "https:" { "wss" } else { "ws" };
+    Some(format!("{}://{}:{}", scheme, host, DEFAULT_PORT))
+}
+
+pub fn connect(url: &str) -> Result<(Sender<ClientMessage>, Receiver<ServerMessage>), String> {
+    let socket = WebSocket::new(url).map_err(|e| format!("{:?}", e))?;
+    let (out_tx, out_rx) = mpsc::channel::<ClientMessage>();
+    let (in_tx, in_rx) = mpsc::channel::<ServerMessage>();
+
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        let Some(text) = event.data().as_string() else { return };
+        match serde_json::from_str::<ServerMessage>(&text) {
+            Ok(message) => {
+                let _ = in_tx.send(message);
+            }
+            Err(e) => web_sys::console::warn_1(&format!("Bad Message: {}", e).into()),
+        }
+    });
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    // ページが開いている間ずっと使うので解放しない
+    on_message.forget();
+
+    SOCKET.with(|cell| *cell.borrow_mut() = Some((socket, out_rx)));
+    Ok((out_tx, in_rx))
+}
+
+// 積まれたメッセージを送る（接続が開くまでは溜めておく）
+// 切断されていたら false。以後の送信はエラーになる
+pub fn flush_outgoing() -> bool {
+    SOCKET.with(|cell| {
+        let connected = match cell.borrow().as_ref() {
+            Some((socket, outgoing)) => send_pending(socket, outgoing),
+            None => false,
+        };
+        if !connected {
+            // 送信チャンネルの受け口を捨てて、送る側に切断を伝える
+            *cell.borrow_mut() = None;
+        }
+        connected
+    })
+}
+
+fn send_pending(socket: &WebSocket, outgoing: &Receiver<ClientMessage>) -> bool {
+    match socket.ready_state() {
+        WebSocket::CONNECTING => return true,
+        WebSocket::OPEN => {}
+        _ => return false,
+    }
+    loop {
+        match outgoing.try_recv() {
+            Ok(message) => {
+                let Ok(text) = serde_json::to_string(&message) else { continue };
+                if socket.send_with_str(&text).is_err() {
+                    return false;
+                }
+            }
+            Err(TryRecvError::Empty) => return true,
+            Err(TryRecvError::Disconnected) => return false,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use crate::map::is_obstacle;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// ==========================================
+// 【新規】クリック / タップ移動の経路探索 (A*)
+// 1 歩は move_player_tick と同じ規則（斜めは X → Y の順に動くので、先に X 方向のマスが空いている必要がある）。
+// 探索は出発点から PATH_SEARCH_RADIUS マス以内、調べるマスは PATH_MAX_NODES 個までに限る
+// （障害物に囲まれた場所を指定しても固まらないように）
+// ==========================================
+
+pub const PATH_SEARCH_RADIUS: i64 = 32;
+pub const PATH_MAX_NODES: usize = 8192;
+
+// from から to へ 1 歩で動けるか（サーバーの is_valid_step と同じ判定）
+pub fn can_step(from: (i64, i64), to: (i64, i64)) -> bool {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    if dx.abs() > 1 || dy.abs() > 1 {
+        return false;
+    }
+    if dx != 0 && is_obstacle(to.0, from.1) {
+        return false;
+    }
+    !is_obstacle(to.0, to.1)
+}
+
+// start から goal までの経路（start を含まず goal を含む）。見つからなければ None
+pub fn find_path(start: (i64, i64), goal: (i64, i64)) -> Option<Vec<(i64, i64)>> {
+    search(start, goal, PATH_MAX_NODES)
+}
+
+fn search(start: (i64, i64), goal: (i64, i64), max_nodes: usize) -> Option<Vec<(i64, i64)>> {
+    let within = |(x, y): (i64, i64)| (x - start.0).abs() <= PATH_SEARCH_RADIUS && (y - start.1).abs() <= PATH_SEARCH_RADIUS;
+    if start == goal || !within(goal) || is_obstacle(goal.0, goal.1) {
+        return None;
+    }
+    // 斜めも 1 歩なので、残りの歩数はチェビシェフ距離
+    let heuristic = |(x, y): (i64, i64)| (x - goal.0).abs().max((y - goal.1).abs());
+
+    let mut open = BinaryHeap::new();
+    let mut cost: HashMap<(i64, i64), i64> = HashMap::new();
+    let mut came_from: HashMap<(i64, i64), (i64, i64)> = HashMap::new();
+    open.push(Reverse((heuristic(start), 0, start)));
+    cost.insert(start, 0);
+
+    let mut expanded = 0;
+    while let Some(Reverse((_, g, cell))) = open.pop() {
+        if cell == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while let Some(&prev) = came_from.get(&current) {
+                if prev == start {
+                    break;
+                }
+                path.push(prev);
+                current = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        // 古い (より遠回りの) エントリは飛ばす
+        if cost.get(&cell).is_some_and(|&best| best < g) {
+            continue;
+        }
+        expanded += 1;
+        if expanded > max_nodes {
+            return None;
+        }
+
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let next = (cell.0 + dx, cell.1 + dy);
+                if next == cell || !within(next) || !can_step(cell, next) {
+                    continue;
+                }
+                let next_g = g + 1;
+                if cost.get(&next).is_none_or(|&best| next_g < best) {
+                    cost.insert(next, next_g);
+                    came_from.insert(next, cell);
+                    open.push(Reverse((next_g + heuristic(next), next_g, next)));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 原点のまわりを渦巻き状に調べて、条件に合う最初のマス
+    fn find_cell(pred: impl Fn(i64, i64) -> bool) -> (i64, i64) {
+        for r in 0..2000i64 {
+            for y in -r..=r {
+                for x in -r..=r {
+                    if (x.abs() == r || y.abs() == r) && pred(x, y) {
+                        return (x, y);
+                    }
+                }
+            }
+        }
+        panic!("no matching cell");
+    }
+
+    fn assert_walkable(start: (i64, i64), path: &[(i64, i64)]) {
+        let mut from = start;
+        for &to in path {
+            assert!(can_step(from, to), "{:?} -> {:?}", from, to);
+            from = to;
+        }
+    }
+
+    // 障害物のマスを指定しても探索しない
+    #[test]
+    fn blocked_goal_has_no_path() {
+        let goal = find_cell(|x, y| is_obstacle(x, y) && (x, y) != (0, 0));
+        assert!(goal.0.abs() <= PATH_SEARCH_RADIUS && goal.1.abs() <= PATH_SEARCH_RADIUS);
+        assert_eq!(find_path((0, 0), goal), None);
+    }
+
+    // 上下左右を障害物に囲まれたマスには斜めからも入れない
+    #[test]
+    fn enclosed_goal_is_unreachable() {
+        let goal = find_cell(|x, y| {
+            !is_obstacle(x, y)
+                && is_obstacle(x - 1, y)
+                && is_obstacle(x + 1, y)
+                && is_obstacle(x, y - 1)
+                && is_obstacle(x, y + 1)
+        });
+        let start = find_cell(|x, y| !is_obstacle(goal.0 + x, goal.1 + y) && x.abs().max(y.abs()) == 5);
+        assert_eq!(find_path((goal.0 + start.0, goal.1 + start.1), goal), None);
+    }
+
+    // 探索範囲の外は指定できない
+    #[test]
+    fn goal_outside_search_radius_is_unreachable() {
+        let x = find_cell(|x, y| y == 0 && x > PATH_SEARCH_RADIUS && !is_obstacle(x, 0)).0;
+        assert_eq!(find_path((0, 0), (x, 0)), None);
+    }
+
+    // 調べるマスが上限を超えたらあきらめる
+    #[test]
+    fn search_gives_up_at_node_limit() {
+        let goal = find_cell(|x, y| y == 0 && x >= 20 && !is_obstacle(x, 0));
+        let path = search((0, 0), goal, PATH_MAX_NODES).expect("open field");
+        assert_walkable((0, 0), &path);
+        assert_eq!(search((0, 0), goal, 3), None);
+    }
+
+    // 斜めは X 方向のマスが空いていないと動けない（Y 方向だけ塞がっているなら動ける）
+    #[test]
+    fn diagonal_step_needs_x_side_free() {
+        let (x, y) = find_cell(|x, y| !is_obstacle(x, y) && is_obstacle(x + 1, y) && !is_obstacle(x + 1, y + 1));
+        assert!(!can_step((x, y), (x + 1, y + 1)));
+        if let Some(path) = find_path((x, y), (x + 1, y + 1)) {
+            assert!(path.len() > 1);
+            assert_walkable((x, y), &path);
+        }
+
+        let (x, y) = find_cell(|x, y| !is_obstacle(x, y) && is_obstacle(x, y + 1) && !is_obstacle(x + 1, y) && !is_obstacle(x + 1, y + 1));
+        assert!(can_step((x, y), (x + 1, y + 1)));
+        assert_eq!(find_path((x, y), (x + 1, y + 1)), Some(vec![(x + 1, y + 1)]));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::content::ItemDef;
+use crate::map::{bot_seed, is_bot_spawn, is_obstacle};
+use serde::{Deserialize, Serialize};
+
+// ==========================================
+// クエスト生成
+// 同じボット（スポーン地点）からは誰が話しかけても同じクエストが出る。
+// 【変更】生成はセリフとアイテム表（編集できるコンテンツ）にもよるので、
+// 受けたときの内容を DB に保存し、あとでコンテンツを変えても受けたクエストは変わらないようにする
+// ==========================================
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum QuestKind {
+    // アイテムを依頼主に届ける
+    Bring { item_id: String },
+    // 指定のマスに行く
+    Visit { x: i64, y: i64 },
+    // 別のボットに言葉を伝える
+    SayWord { word: String, bot_x: i64, bot_y: i64 },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum QuestReward {
+    Word(String),
+    Emoji(String),
+    Points(i32),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Quest {
+    pub giver_x: i64,
+    pub giver_y: i64,
+    pub kind: QuestKind,
+    pub reward: QuestReward,
+}
+
+// 【新規】DB に保存したクエスト
+// 内容を保存するようになる前に受けたものは quest が None（そのときは座標から作り直す）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedQuest {
+    pub giver_x: i64,
+    pub giver_y: i64,
+    pub done: bool,
+    pub quest: Option<Quest>,
+}
+
+// 【変更】セリフもスポーン地点から決定的に選ぶ
+// (同じ場所のボットは同じことを言う → クエストのヒントとして共有できる)
+// サーバーもクエストを作るので systems::bot から移した
+pub fn dialogue_for(x: i64, y: i64, lines: &[String]) -> String {
+    if lines.is_empty() {
+        return "...".to_string();
+    }
+    // 性格 (下位ビット) とは別のビットを使う
+    lines[((bot_seed(x, y) >> 16) % lines.len() as u64) as usize].clone()
+}
+
+// セリフに方角が含まれるボットは、その方角への Visit クエストを出す
+// (例: "南の方角に何かあるらしいぞ")
+const HINT_DIRECTIONS: [(&str, i64, i64); 4] = [
+    ("南", 0, -1),
+    ("北", 0, 1),
+    ("東", 1, 0),
+    ("西", -1, 0),
+];
+
+// SayWord で伝える言葉（初期の語彙から選ぶので必ず話せる）
+const SAY_WORDS: [&str; 3] = ["Hello", "Yes", "No"];
+
+const REWARD_WORDS: [&str; 8] = [
+    "Thanks", "Friend", "Food", "Water", "Home", "Look", "Secret", "Treasure",
+];
+const REWARD_EMOJIS: [&str; 5] = ["🌟", "🌈", "🐱", "🎵", "🌸"];
+
+// SayWord の相手を探す範囲
+const PARTNER_SEARCH_RADIUS: i64 = 40;
+
+// シードから順に値を取り出す (splitmix64)
+struct QuestRng(u64);
+
+impl QuestRng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+pub fn generate_quest(giver_x: i64, giver_y: i64, dialogue: &str, items: &[ItemDef]) -> Quest {
+    let mut rng = QuestRng(bot_seed(giver_x, giver_y) ^ 0x5155_4553_5400);
+
+    let hint = HINT_DIRECTIONS.iter().find(|(word, _, _)| dialogue.contains(word));
+    let kind = if let Some(&(_, dx, dy)) = hint {
+        visit_towards(giver_x, giver_y, dx, dy, &mut rng)
+    } else {
+        match rng.below(3) {
+            0 if !items.is_empty() => QuestKind::Bring {
+                item_id: items[rng.below(items.len())].id.clone(),
+            },
+            1 => match find_partner_bot(giver_x, giver_y) {
+                Some((bot_x, bot_y)) => QuestKind::SayWord {
+                    word: SAY_WORDS[rng.below(SAY_WORDS.len())].to_string(),
+                    bot_x,
+                    bot_y,
+                },
+                None => random_visit(giver_x, giver_y, &mut rng),
+            },
+            _ => random_visit(giver_x, giver_y, &mut rng),
+        }
+    };
+
+    let reward = match rng.below(3) {
+        0 => QuestReward::Word(REWARD_WORDS[rng.below(REWARD_WORDS.len())].to_string()),
+        1 => QuestReward::Emoji(REWARD_EMOJIS[rng.below(REWARD_EMOJIS.len())].to_string()),
+        _ => QuestReward::Points(1 + rng.below(5) as i32),
+    };
+
+    Quest { giver_x, giver_y, kind, reward }
+}
+
+fn random_visit(x: i64, y: i64, rng: &mut QuestRng) -> QuestKind {
+    let (_, dx, dy) = HINT_DIRECTIONS[rng.below(HINT_DIRECTIONS.len())];
+    visit_towards(x, y, dx, dy, rng)
+}
+
+// (dx, dy) 方向に 20〜60 マス、左右に ±5 マスずらした障害物でないマス
+fn visit_towards(x: i64, y: i64, dx: i64, dy: i64, rng: &mut QuestRng) -> QuestKind {
+    let dist = 20 + rng.below(41) as i64;
+    let lateral = rng.below(11) as i64 - 5;
+
+    let (mut tx, ty) = if dx != 0 {
+        (x + dx * dist, y + lateral)
+    } else {
+        (x + lateral, y + dy * dist)
+    };
+    while is_obstacle(tx, ty) {
+        tx += 1;
+    }
+
+    QuestKind::Visit { x: tx, y: ty }
+}
+
+// 依頼主から近い順（内側のリングから）に別のボットを探す
+fn find_partner_bot(x: i64, y: i64) -> Option<(i64, i64)> {
+    for r in 3..=PARTNER_SEARCH_RADIUS {
+        for i in -r..=r {
+            for (cx, cy) in [(x + i, y + r), (x + i, y - r), (x + r, y + i), (x - r, y + i)] {
+                if is_bot_spawn(cx, cy) {
+                    return Some((cx, cy));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn item_name(items: &[ItemDef], item_id: &str) -> String {
+    items
+        .iter()
+        .find(|item| item.id == item_id)
+        .map(|item| item.name.clone())
+        .unwrap_or_else(|| item_id.to_string())
+}
+
+impl Quest {
+    // 依頼するときの吹き出し
+    pub fn offer_text(&self, items: &[ItemDef]) -> String {
+        match &self.kind {
+            QuestKind::Bring { item_id } => format!("{}を持ってきてくれない？", item_name(items, item_id)),
+            QuestKind::Visit { x, y } => format!("({}, {}) に行ってみて！", x, y),
+            QuestKind::SayWord { word, bot_x, bot_y } => {
+                format!("({}, {}) あたりのボットに「{}」と伝えて！", bot_x, bot_y, word)
+            }
+        }
+    }
+
+    // クエストログの1行
+    pub fn log_text(&self, items: &[ItemDef]) -> String {
+        match &self.kind {
+            QuestKind::Bring { item_id } => {
+                format!("Bring {} to ({}, {})", item_name(items, item_id), self.giver_x, self.giver_y)
+            }
+            QuestKind::Visit { x, y } => format!("Visit ({}, {})", x, y),
+            QuestKind::SayWord { word, bot_x, bot_y } => {
+                format!("Say \"{}\" to the bot near ({}, {})", word, bot_x, bot_y)
+            }
+        }
+    }
+
+    pub fn reward_text(&self) -> String {
+        match &self.reward {
+            QuestReward::Word(word) => format!("word \"{}\"", word),
+            QuestReward::Emoji(emoji) => format!("emoji {}", emoji),
+            QuestReward::Points(points) => format!("{} pts", points),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // セリフの方角と Visit の行き先の向きが合っているか（ボットが言うセリフからクエストを作る前提）
+    #[test]
+    fn hint_direction_matches_visit_target() {
+        let lines: Vec<String> = HINT_DIRECTIONS.iter().map(|(word, _, _)| format!("{}の方角に何かあるらしいぞ", word)).collect();
+        for (gx, gy) in [(0, 0), (123, -45), (-1000, 777), (31, 8), (-5, -5000)] {
+            // そのボットが言うセリフと、ほかのどのセリフでも
+            let spoken = dialogue_for(gx, gy, &lines);
+            for dialogue in std::iter::once(&spoken).chain(&lines) {
+                let &(_, dx, dy) = HINT_DIRECTIONS.iter().find(|(word, _, _)| dialogue.contains(word)).unwrap();
+                let QuestKind::Visit { x, y } = generate_quest(gx, gy, dialogue, &[]).kind else {
+                    panic!("hint line should give a Visit quest: {}", dialogue);
+                };
+                let along = (x - gx) * dx + (y - gy) * dy;
+                let across = if dx != 0 { y - gy } else { x - gx };
+                assert!(along >= 15 && along > across.abs(), "{} from ({}, {}) went to ({}, {})", dialogue, gx, gy, x, y);
+            }
+        }
+    }
+}
@@ -1,7 +1,17 @@
 use bevy::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
 use r2d2::Pool;
+#[cfg(not(target_arch = "wasm32"))]
 use r2d2_postgres::PostgresConnectionManager;
+#[cfg(not(target_arch = "wasm32"))]
 use postgres::NoTls;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::net::ServerEvent;
+use crate::net::{ClientId, ClientMessage, ServerMessage};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Mutex;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashMap;
 
 #[derive(Resource)]
 pub struct MoveTimer(pub Timer);
@@ -15,6 +25,10 @@ impl MoveTimer {
 #[derive(Resource)]
 pub struct InputBuffer(pub Vec2);
 
+// 【新規】クリック / タップで決めた経路（先頭から 1 歩ずつ進む）
+#[derive(Resource, Default)]
+pub struct MovePath(pub std::collections::VecDeque<(i64, i64)>);
+
 #[derive(Resource)]
 pub struct ChatLog {
     pub messages: Vec<(String, Timer)>,
@@ -30,12 +44,15 @@ pub struct ChatMenuState {
 pub struct EmojiConfig {
     pub s_key: String,
     pub d_key: String,
+    // 【新規】クエスト報酬などで手に入れた絵文字 (EmojiCatalog に追加で選べる)
+    pub unlocked: Vec<String>,
 }
 
 #[derive(Resource)]
 pub struct EmojiSelectState {
     pub is_open: bool,
-    pub target_key: Option<KeyCode>,
+    // 【変更】どちらの絵文字 (EmoteS / EmoteD) を選んでいるか
+    pub target_key: Option<crate::controls::Action>,
     pub selected_index: usize,
 }
 
@@ -74,6 +91,22 @@ pub struct CurrentUser {
     pub grid_x: i64,
     pub grid_y: i64,
     pub words: Vec<String>,
+    pub items: Vec<String>,
+    pub points: i32,
+    // 【変更】受けたクエスト（内容を保存する前のものは Playing に入るときに作り直す）
+    pub quests: Vec<crate::quest::SavedQuest>,
+    // 【新規】ミュートしているユーザー名（この人たちの言葉は表示しない）
+    pub muted: Vec<String>,
+    // 【新規】ログイン時に読んだ探索済みのチャンク（Playing に入るときに Explored に移す）
+    pub explored: Vec<crate::explore::ChunkBits>,
+    pub explored_counted: Vec<crate::explore::ChunkCount>,
+    pub explored_cells: i64,
+    // 【新規】操作の割り当て（controls::Controls::to_saved の形。空なら既定）
+    pub key_bindings: String,
+    // 【新規】ウェイポイント（Playing に入るときに Waypoints に移す）
+    pub waypoints: Vec<Waypoint>,
+    // 【新規】拾ったアイテムのマス（Playing に入るときに TakenItems に移す）
+    pub taken_items: Vec<(i64, i64)>,
 }
 
 // Defaultの実装
@@ -84,16 +117,146 @@ impl Default for CurrentUser {
             grid_x: 0,
             grid_y: 0,
             words: Vec::new(),
+            items: Vec::new(),
+            points: 0,
+            quests: Vec::new(),
+            muted: Vec::new(),
+            explored: Vec::new(),
+            explored_counted: Vec::new(),
+            explored_cells: 0,
+            key_bindings: String::new(),
+            waypoints: Vec::new(),
+            taken_items: Vec::new(),
         }
     }
 }
 
+// 【新規】クエストログ
+pub struct QuestEntry {
+    pub quest: crate::quest::Quest,
+    pub done: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct QuestLog {
+    pub entries: Vec<QuestEntry>,
+    pub is_open: bool,
+}
+
+impl QuestLog {
+    pub fn find(&self, giver_x: i64, giver_y: i64) -> Option<&QuestEntry> {
+        self.entries.iter().find(|e| e.quest.giver_x == giver_x && e.quest.giver_y == giver_y)
+    }
+}
+
+// 【新規】拾ったアイテムのマス（同じマスから何度も拾えないように）
+// 【変更】DB の taken_items から読むので、ログインし直しても戻らない
+#[derive(Resource, Default)]
+pub struct TakenItems(pub std::collections::HashSet<(i64, i64)>);
+
+// 【新規】ボットの記憶を DB に書き込む間隔
+#[derive(Resource)]
+pub struct BotMemorySaveTimer(pub Timer);
+
 // 【新規】セリフデータを保持するリソース
 #[derive(Resource, Default)]
 pub struct BotDialogues {
     pub lines: Vec<String>,
 }
 
+// 絵文字選択メニューに並ぶ候補 (assets/emoji_list.txt から読み込み、ホットリロード対応)
+#[derive(Resource)]
+pub struct EmojiCatalog {
+    pub emojis: Vec<String>,
+}
+
+impl EmojiCatalog {
+    // 選択メニューに並べる候補 (共通の候補 + 手に入れた絵文字)
+    pub fn choices<'a>(&'a self, unlocked: &'a [String]) -> Vec<&'a String> {
+        self.emojis
+            .iter()
+            .chain(unlocked.iter().filter(|e| !self.emojis.contains(e)))
+            .collect()
+    }
+}
+
+// ファイルが読めるまでは組み込みのリストを使う
+impl Default for EmojiCatalog {
+    fn default() -> Self {
+        Self {
+            emojis: EMOJI_LIST.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+// 【新規】サーバーが判定に使うコンテンツ
+// サーバーはアセットを読み込まないので、起動時に CONTENT_DIR (既定は assets) のファイルを読む
+// 【変更】クエストを作るためのセリフとアイテム表も読む（読めなければクライアントの読み込み前と同じく空）
+#[derive(Resource)]
+pub struct ServerContent {
+    pub emojis: Vec<String>,
+    pub dialogues: Vec<String>,
+    pub items: Vec<crate::content::ItemDef>,
+}
+
+impl ServerContent {
+    pub fn from_env() -> Self {
+        let dir = std::path::PathBuf::from(std::env::var("CONTENT_DIR").unwrap_or_else(|_| "assets".to_string()));
+        let read = |name: &str| crate::content::read_content_file(&dir.join(name));
+        let emojis = read("emoji_list.txt")
+            .and_then(|lines| crate::content::parse_emoji_list(&lines))
+            .unwrap_or_else(|e| {
+                eprintln!("Content Error (emoji_list.txt): {}", e);
+                EmojiCatalog::default().emojis
+            });
+        let dialogues = read("bot_dialogues.txt").unwrap_or_else(|e| {
+            eprintln!("Content Error (bot_dialogues.txt): {}", e);
+            Vec::new()
+        });
+        let items = read("item_table.txt")
+            .and_then(|lines| crate::content::parse_item_table(&lines))
+            .unwrap_or_else(|e| {
+                eprintln!("Content Error (item_table.txt): {}", e);
+                Vec::new()
+            });
+        ServerContent { emojis, dialogues, items }
+    }
+
+    // 【新規】依頼主の座標からクエストを作る（クライアントの handle_quest_speech と同じ作り方）
+    pub fn quest_at(&self, giver_x: i64, giver_y: i64) -> crate::quest::Quest {
+        let dialogue = crate::quest::dialogue_for(giver_x, giver_y, &self.dialogues);
+        crate::quest::generate_quest(giver_x, giver_y, &dialogue, &self.items)
+    }
+
+    // 【新規】このプレイヤーが出してよい絵文字か（固定の 👍・共通の候補・手に入れた絵文字）
+    pub fn allows_emoji(&self, emoji: &str, unlocked: &std::collections::HashSet<String>) -> bool {
+        emoji == THUMB_EMOJI || self.emojis.iter().any(|e| e == emoji) || unlocked.contains(emoji)
+    }
+}
+
+// 【新規】ホットリロード対象のコンテンツファイルのハンドル
+#[derive(Resource)]
+pub struct ContentHandles {
+    pub bot_dialogues: Handle<crate::content::ContentText>,
+    pub emoji_list: Handle<crate::content::ContentText>,
+    pub item_table: Handle<crate::content::ContentText>,
+}
+
+// 【新規】アイテム表 (assets/item_table.txt から読み込み、ホットリロード対応)
+#[derive(Resource, Default)]
+pub struct ItemTable {
+    pub items: Vec<crate::content::ItemDef>,
+}
+
+impl ItemTable {
+    pub fn get(&self, id: &str) -> Option<&crate::content::ItemDef> {
+        self.items.iter().find(|item| item.id == id)
+    }
+}
+
+// 【新規】いつでも出せる固定の絵文字 (Action::EmoteThumb)
+pub const THUMB_EMOJI: &str = "👍";
+
 pub const EMOJI_LIST: [&str; 20] = [
     "😁", "😭", "😡", "😇", "🤔", 
     "🤮", "💩", "👻", "💀", "👽",
@@ -101,5 +264,91 @@ pub const EMOJI_LIST: [&str; 20] = [
     "💤", "❤️", "💔", "👀", "🧠"
 ];
 
+// ブラウザ版には DB がない（クライアントは storage::Storage 経由で保存する）
+#[cfg(not(target_arch = "wasm32"))]
 #[derive(Resource, Clone)]
-pub struct DbPool(pub Pool<PostgresConnectionManager<NoTls>>);
\ No newline at end of file
+pub struct DbPool(pub Pool<PostgresConnectionManager<NoTls>>);
+
+// 【新規】サーバーモードの接続一覧
+// Receiver は Sync でないので Mutex で包む
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+pub struct NetServer {
+    pub events: Mutex<Receiver<ServerEvent>>,
+    pub clients: HashMap<ClientId, Sender<ServerMessage>>,
+    // Hello を送ってきたクライアントのプレイヤーエンティティ
+    pub players: HashMap<ClientId, Entity>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NetServer {
+    pub fn send(&self, id: ClientId, message: ServerMessage) {
+        if let Some(sender) = self.clients.get(&id) {
+            let _ = sender.send(message);
+        }
+    }
+}
+
+// 【新規】クライアントのサーバー接続（GAME_SERVER が設定されているとき・ブラウザ版のときだけ存在する）
+#[derive(Resource)]
+pub struct NetClient {
+    pub outgoing: Sender<ClientMessage>,
+    pub incoming: Mutex<Receiver<ServerMessage>>,
+    // LoggedIn で割り当てられる自分の ID
+    pub id: Option<ClientId>,
+    // 最後にサーバーと合意した位置（補正で戻されたときに Move を送り返さないため）
+    pub synced_position: Option<(i64, i64)>,
+}
+
+impl NetClient {
+    pub fn send(&self, message: ClientMessage) {
+        let _ = self.outgoing.send(message);
+    }
+}
+
+// 【新規】スナップショット送信の間隔
+#[derive(Resource)]
+pub struct SnapshotTimer(pub Timer);
+
+// 【新規】サーバーがプレイヤーの位置を DB に書き込む間隔
+#[derive(Resource)]
+pub struct PositionSaveTimer(pub Timer);
+
+// 【新規】自分の言葉の連投制限
+#[derive(Resource, Default)]
+pub struct ChatRateLimit(pub crate::chat::RateLimiter);
+
+// 【新規】presence テーブルを読み書きする間隔
+#[derive(Resource)]
+pub struct PresenceTimer(pub Timer);
+// 【新規】保存した地点（ミニマップ・ワールドマップに印を出す）
+// 【変更】ユーザーごとに DB に保存し、テレポート石から飛べる
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct Waypoint {
+    pub name: String,
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Waypoint {
+    // 名前は 1〜MAX_WAYPOINT_NAME_LEN 文字の英数字・空白・ハイフン（前後の空白は不可）
+    pub fn is_valid_name(name: &str) -> bool {
+        let len = name.chars().count();
+        (1..=crate::constants::MAX_WAYPOINT_NAME_LEN).contains(&len)
+            && name.trim() == name
+            && name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-')
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Waypoints {
+    pub points: Vec<Waypoint>,
+}
+
+// 【新規】探索済みの場所
+#[derive(Resource, Default)]
+pub struct Explored(pub crate::explore::ExploredMap);
+
+// 【新規】探索済みの場所を DB に書き込む間隔
+#[derive(Resource)]
+pub struct ExploreSaveTimer(pub Timer);
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use crate::constants::{tick_rate_from_env, SNAPSHOT_INTERVAL};
+use crate::resources::*;
+use crate::spatial::SpatialIndex;
+use crate::stamina::StaminaConfig;
+use crate::systems::bot_wander::bot_wander_system;
+use crate::systems::net_server::*;
+use crate::systems::spatial::update_spatial_index;
+
+// ==========================================
+// サーバーのシミュレーション一式
+// MinimalPlugins と組み合わせて使う（ウィンドウ・描画・アセットなし）
+// 【変更】クライアントと同じく固定ティック (FixedUpdate, TICK_RATE) で動かし、
+// ボットの移動やスナップショットの間隔がフレームレートによらないようにする
+// ==========================================
+pub struct ServerPlugin;
+
+impl Plugin for ServerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>()
+            .insert_resource(StaminaConfig::from_env())
+            .insert_resource(ServerContent::from_env())
+            .insert_resource(SnapshotTimer(Timer::from_seconds(SNAPSHOT_INTERVAL, TimerMode::Repeating)))
+            .insert_resource(PositionSaveTimer(Timer::from_seconds(30.0, TimerMode::Repeating)))
+            .insert_resource(Time::<Fixed>::from_hz(tick_rate_from_env()))
+            .add_systems(Startup, setup_server)
+            .add_systems(FixedUpdate, (
+                receive_client_messages,
+                spawn_server_bots,
+                despawn_server_bots,
+                update_spatial_index,
+                bot_wander_system,
+                send_snapshots,
+                save_player_positions,
+            ).chain());
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// ==========================================
+// 空間インデックス
+// グリッドのマス → そのマスにいるエンティティ (GridPosition を持つもの)
+// 「周囲 r マスにいるのは誰か」「このマスは空いているか」を
+// 全エンティティを回さずに、マス単位の定数時間で調べられる
+// ==========================================
+#[derive(Resource, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<(i64, i64), Vec<Entity>>,
+    positions: HashMap<Entity, (i64, i64)>,
+}
+
+impl SpatialIndex {
+    // エンティティの位置を登録・更新する
+    pub fn set(&mut self, entity: Entity, cell: (i64, i64)) {
+        if let Some(old) = self.positions.insert(entity, cell) {
+            if old == cell {
+                return;
+            }
+            self.remove_from_cell(entity, old);
+        }
+        self.cells.entry(cell).or_default().push(entity);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(old) = self.positions.remove(&entity) {
+            self.remove_from_cell(entity, old);
+        }
+    }
+
+    fn remove_from_cell(&mut self, entity: Entity, cell: (i64, i64)) {
+        if let Some(list) = self.cells.get_mut(&cell) {
+            list.retain(|&e| e != entity);
+            if list.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    pub fn at(&self, cell: (i64, i64)) -> &[Entity] {
+        self.cells.get(&cell).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn is_occupied(&self, cell: (i64, i64)) -> bool {
+        self.cells.contains_key(&cell)
+    }
+
+    // 【新規】cell から半径 radius マス以内 (ユークリッド距離) にいるエンティティ
+    pub fn within_radius(&self, cell: (i64, i64), radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let range = radius.floor() as i64;
+        (-range..=range).flat_map(move |dx| {
+            (-range..=range)
+                .filter(move |dy| ((dx * dx + dy * dy) as f32) <= radius * radius)
+                .flat_map(move |dy| self.at((cell.0 + dx, cell.1 + dy)).iter().copied())
+        })
+    }
+
+    // cell から縦横 range マス以内 (チェビシェフ距離) にいるエンティティ
+    pub fn within(&self, cell: (i64, i64), range: i64) -> impl Iterator<Item = Entity> + '_ {
+        (-range..=range).flat_map(move |dx| {
+            (-range..=range).flat_map(move |dy| self.at((cell.0 + dx, cell.1 + dy)).iter().copied())
+        })
+    }
+}
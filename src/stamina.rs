@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use crate::constants::PLAYER_MOVE_INTERVAL;
+
+// ==========================================
+// 【新規】ダッシュとスタミナ
+// ダッシュ中は 1 歩の間隔が SPRINT_INTERVAL になり、1 歩ごとにスタミナを STEP_COST 使う。
+// スタミナは時間で回復する（クライアントはダッシュしていない間だけ、サーバーはいつも。
+// なのでサーバーの見積もりがクライアントより少なくなることはない）。
+// 値は環境変数で変えられる。サーバーと DB の移動の判定も同じ設定を使う
+// ==========================================
+
+pub const DEFAULT_STAMINA_MAX: f32 = 100.0;
+pub const DEFAULT_SPRINT_INTERVAL: f32 = 0.35;
+pub const DEFAULT_STEP_COST: f32 = 10.0;
+pub const DEFAULT_STAMINA_REGEN: f32 = 5.0;
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct StaminaConfig {
+    pub max: f32,
+    // ダッシュ中の 1 歩の間隔 (秒)。PLAYER_MOVE_INTERVAL より長くはしない
+    pub sprint_interval: f32,
+    // 1 歩あたりに使うスタミナ
+    pub step_cost: f32,
+    // 1 秒あたりに回復するスタミナ
+    pub regen_per_sec: f32,
+}
+
+impl Default for StaminaConfig {
+    fn default() -> Self {
+        StaminaConfig {
+            max: DEFAULT_STAMINA_MAX,
+            sprint_interval: DEFAULT_SPRINT_INTERVAL,
+            step_cost: DEFAULT_STEP_COST,
+            regen_per_sec: DEFAULT_STAMINA_REGEN,
+        }
+    }
+}
+
+fn env_f32(name: &str) -> Option<f32> {
+    std::env::var(name).ok().and_then(|v| v.parse::<f32>().ok()).filter(|v| v.is_finite() && *v >= 0.0)
+}
+
+impl StaminaConfig {
+    // STAMINA_MAX / SPRINT_INTERVAL / STAMINA_STEP_COST / STAMINA_REGEN（なければ既定値）
+    pub fn from_env() -> Self {
+        let defaults = StaminaConfig::default();
+        StaminaConfig {
+            max: env_f32("STAMINA_MAX").unwrap_or(defaults.max),
+            sprint_interval: env_f32("SPRINT_INTERVAL")
+                .filter(|v| *v > 0.0)
+                .unwrap_or(defaults.sprint_interval)
+                .min(PLAYER_MOVE_INTERVAL),
+            step_cost: env_f32("STAMINA_STEP_COST").unwrap_or(defaults.step_cost),
+            regen_per_sec: env_f32("STAMINA_REGEN").unwrap_or(defaults.regen_per_sec),
+        }
+    }
+
+    // elapsed 秒の間に歩ける最大の歩数（満タンから始めて、使えるだけダッシュした場合）
+    pub fn max_steps(&self, elapsed: f64) -> f64 {
+        let walk = elapsed / PLAYER_MOVE_INTERVAL as f64;
+        let sprint_steps = if self.step_cost > 0.0 {
+            ((self.max as f64 + self.regen_per_sec as f64 * elapsed) / self.step_cost as f64).min(elapsed / self.sprint_interval as f64)
+        } else {
+            elapsed / self.sprint_interval as f64
+        };
+        // ダッシュの 1 歩は、歩きより (1 - sprint / walk) 歩ぶん得をする
+        walk + sprint_steps * (1.0 - self.sprint_interval as f64 / PLAYER_MOVE_INTERVAL as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALK: f64 = PLAYER_MOVE_INTERVAL as f64;
+
+    // クライアントと同じ動き方（スタミナがあればダッシュ、歩いている間だけ回復）で elapsed 秒に歩ける歩数
+    fn simulate(config: &StaminaConfig, elapsed: f64) -> i64 {
+        let (mut t, mut stamina, mut steps) = (0.0, config.max as f64, 0);
+        loop {
+            let sprint = stamina >= config.step_cost as f64;
+            let interval = if sprint { config.sprint_interval as f64 } else { WALK };
+            if t + interval > elapsed {
+                return steps;
+            }
+            t += interval;
+            steps += 1;
+            if sprint {
+                stamina -= config.step_cost as f64;
+            } else {
+                stamina = (stamina + config.regen_per_sec as f64 * interval).min(config.max as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_elapsed_allows_no_steps() {
+        assert_eq!(StaminaConfig::default().max_steps(0.0), 0.0);
+    }
+
+    // スタミナがなければ歩きの歩数ちょうど
+    #[test]
+    fn exhausted_stamina_only_walks() {
+        let config = StaminaConfig { max: 0.0, regen_per_sec: 0.0, ..default() };
+        assert_eq!(config.max_steps(10.0), 10.0 / WALK);
+        assert_eq!(simulate(&config, 10.0) as f64, 10.0 / WALK);
+    }
+
+    // 短い間はずっとダッシュできる
+    #[test]
+    fn short_sprint_is_bounded_by_sprint_interval() {
+        let config = StaminaConfig::default();
+        let elapsed = 2.0;
+        let expected = elapsed / config.sprint_interval as f64;
+        assert!((config.max_steps(elapsed) - expected).abs() < 1e-9);
+    }
+
+    // 長い間はスタミナが尽きるので、歩きとダッシュの間に収まる
+    #[test]
+    fn long_sprint_is_limited_by_stamina() {
+        let config = StaminaConfig::default();
+        let elapsed = 100.0;
+        let steps = config.max_steps(elapsed);
+        assert!(steps > elapsed / WALK);
+        assert!(steps < elapsed / config.sprint_interval as f64);
+    }
+
+    // 正しく動くクライアントの歩数が見積もりを超えない（超えるとサーバーと DB が正しい移動を戻してしまう）
+    #[test]
+    fn honest_client_stays_within_bound() {
+        let configs = [
+            StaminaConfig::default(),
+            StaminaConfig { max: 0.0, regen_per_sec: 0.0, ..default() },
+            StaminaConfig { step_cost: 0.0, ..default() },
+            StaminaConfig { regen_per_sec: 50.0, ..default() },
+        ];
+        for config in configs {
+            for elapsed in [0.0, 0.3, 1.0, 3.5, 10.0, 30.0, 120.0] {
+                let steps = simulate(&config, elapsed);
+                assert!(steps as f64 <= config.max_steps(elapsed).ceil(), "{:?} {}s: {} steps", config, elapsed, steps);
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use crate::net::ClientMessage;
+use crate::explore::{ChunkBits, ChunkCount};
+use crate::quest::Quest;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{database, resources::DbPool};
+use std::sync::mpsc::Sender;
+
+// ==========================================
+// 【新規】クライアントの保存先
+// Local  = 今まで通り DB に直接書く（ネイティブ版で一人で遊ぶとき）
+// Remote = サーバー経由（サーバー接続中・ブラウザ版）。書き込みは送りっぱなしで、
+//          読み込み（ログイン・ボットの記憶）の返事は net_client が受け取る
+// ==========================================
+#[derive(Resource)]
+pub enum Storage {
+    #[cfg(not(target_arch = "wasm32"))]
+    Local(DbPool),
+    Remote(Sender<ClientMessage>),
+}
+
+impl Storage {
+    fn send(sender: &Sender<ClientMessage>, message: ClientMessage) -> Result<(), String> {
+        sender.send(message).map_err(|_| "Disconnected from server".to_string())
+    }
+
+    // 位置はサーバー接続中ならサーバーのものが保存される
+    // 【変更】DB 直接で位置が認められなかったときは Some(DB に残っている位置)。
+    // サーバー接続中はサーバーが Correction で戻すので None
+    pub fn save_user(
+        &self,
+        username: &str,
+        x: i64,
+        y: i64,
+        words: Vec<String>,
+        s_key: &str,
+        d_key: &str,
+        items: Vec<String>,
+    ) -> Result<Option<(i64, i64)>, String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::save_user_data(&pool.0, username, x, y, words, s_key, d_key, items),
+            // 【変更】言葉と持ち物はサーバーが書くので送らない
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::SaveProgress {
+                s_key: s_key.to_string(),
+                d_key: d_key.to_string(),
+            }).map(|_| None),
+        }
+    }
+
+    // 【変更】内容も保存する（サーバー接続中はサーバーが同じ座標から作って保存する）
+    pub fn accept_quest(&self, username: &str, quest: &Quest) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::accept_quest(&pool.0, username, quest),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::AcceptQuest { giver_x: quest.giver_x, giver_y: quest.giver_y }),
+        }
+    }
+
+    // 【新規】アイテムを拾ったマスを記録する（サーバー接続中はサーバーが移動を認めたときに記録する）
+    pub fn take_item(&self, username: &str, x: i64, y: i64, item_id: &str) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::take_item(&pool.0, username, x, y, item_id).map(|_| ()),
+            Storage::Remote(_) => Ok(()),
+        }
+    }
+
+    // 【変更】報酬は DB に保存されている持ち物に足す（サーバー接続中はサーバーが判定して足す）
+    pub fn complete_quest(&self, username: &str, quest: &Quest) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::complete_quest(&pool.0, username, quest).map(|_| ()),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::CompleteQuest { giver_x: quest.giver_x, giver_y: quest.giver_y }),
+        }
+    }
+
+    // 【新規】DB の記憶に足し合わせる（読み込む前のボットの記憶を書くとき）
+    pub fn add_bot_memory(
+        &self,
+        bot_x: i64,
+        bot_y: i64,
+        username: &str,
+        times_greeted: i32,
+        last_seen: i64,
+        affinity: i32,
+    ) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => {
+                database::add_bot_memory(&pool.0, bot_x, bot_y, username, times_greeted, last_seen, affinity)
+            }
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::AddBotMemory {
+                bot_x,
+                bot_y,
+                times_greeted,
+                last_seen,
+                affinity,
+            }),
+        }
+    }
+
+    pub fn save_bot_memory(
+        &self,
+        bot_x: i64,
+        bot_y: i64,
+        username: &str,
+        times_greeted: i32,
+        last_seen: i64,
+        affinity: i32,
+    ) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => {
+                database::save_bot_memory(&pool.0, bot_x, bot_y, username, times_greeted, last_seen, affinity)
+            }
+            // サーバーはログインしているユーザーの分しか書かない
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::SaveBotMemory {
+                bot_x,
+                bot_y,
+                times_greeted,
+                last_seen,
+                affinity,
+            }),
+        }
+    }
+
+    pub fn set_muted(&self, username: &str, target: &str, muted: bool) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::set_muted(&pool.0, username, target, muted),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::SetMuted { target: target.to_string(), muted }),
+        }
+    }
+
+    // 【新規】探索済みの場所。サーバー接続中はサーバーが移動から記録するので送らない
+    pub fn save_explored(&self, username: &str, chunks: &[ChunkBits], counted: &[ChunkCount], cells: i64) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::save_explored(&pool.0, username, chunks, counted, cells),
+            Storage::Remote(_) => Ok(()),
+        }
+    }
+
+    // 【新規】ウェイポイント。サーバー接続中の位置はサーバーのものを使う
+    pub fn add_waypoint(&self, username: &str, name: &str, x: i64, y: i64) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::add_waypoint(&pool.0, username, name, x, y),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::AddWaypoint { name: name.to_string() }),
+        }
+    }
+
+    pub fn delete_waypoint(&self, username: &str, name: &str) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::delete_waypoint(&pool.0, username, name),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::DeleteWaypoint { name: name.to_string() }),
+        }
+    }
+
+    // 【新規】テレポート。DB 直接ならすぐに行き先を返す。サーバー接続中は None（行き先は Correction で届く）
+    pub fn teleport(&self, username: &str, name: &str, from: (i64, i64)) -> Result<Option<(i64, i64)>, String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::teleport_to_waypoint(&pool.0, username, name, from).map(Some),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::Teleport { name: name.to_string() }).map(|_| None),
+        }
+    }
+
+    // 【新規】操作の割り当て
+    pub fn save_bindings(&self, username: &str, bindings: &str) -> Result<(), String> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(pool) => database::save_bindings(&pool.0, username, bindings),
+            Storage::Remote(sender) => Self::send(sender, ClientMessage::SaveBindings { bindings: bindings.to_string() }),
+        }
+    }
+}
@@ -2,7 +2,11 @@ use bevy::prelude::*;
 use bevy::input::keyboard::{KeyboardInput, Key};
 use crate::resources::*;
 // use crate::components::*; // Playerなどはここでは触らないので削除OK
+#[cfg(not(target_arch = "wasm32"))]
 use crate::database; 
+use crate::net::{ClientMessage, UserProfile};
+use crate::storage::Storage;
+use crate::controls::{Action, ActionState};
 use std::fs::File;
 use std::io::Write;
 
@@ -52,31 +56,36 @@ pub fn setup_account_ui(
     });
 }
 
+// ブラウザ版は DB 直接の分岐がないので使わない引数がある
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables, unused_mut))]
 pub fn handle_account_input(
     mut keyboard_events: EventReader<KeyboardInput>,
+    // Backspace は文字の入力の一部なのでキーのまま読む
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     mut state: ResMut<AccountState>,
     mut next_state: ResMut<NextState<GameState>>,
     mut text_query: Query<&mut Text, With<AccountInputText>>,
     
     mut notification: ResMut<NotificationState>,
-    db_pool: Res<DbPool>,
+    // 【変更】DB 直接 or サーバー経由
+    storage: Res<Storage>,
     
     // データを反映するためのリソース
     mut current_user: ResMut<CurrentUser>,
     // mut player_query: Query<...> ← これは削除（まだプレイヤーがいないため）
     mut emoji_config: ResMut<EmojiConfig>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+    if actions.just_pressed(Action::MoveLeft) {
         state.mode = AccountMode::Login;
         state.error_msg = "".to_string();
     }
-    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+    if actions.just_pressed(Action::MoveRight) {
         state.mode = AccountMode::Create;
         state.error_msg = "".to_string();
     }
 
-    if keyboard_input.just_pressed(KeyCode::Tab) {
+    if actions.just_pressed(Action::SwitchInput) {
         state.is_typing_password = !state.is_typing_password;
     }
 
@@ -101,66 +110,31 @@ pub fn handle_account_input(
         if !state.is_typing_password { state.username.pop(); } else { state.password.pop(); }
     }
 
-    if keyboard_input.just_pressed(KeyCode::Enter) {
+    if actions.just_pressed(Action::Confirm) {
         if state.username.is_empty() || state.password.is_empty() {
             state.error_msg = "Input missing!".to_string();
         } else {
-            match state.mode {
-                AccountMode::Login => {
-                    match database::verify_user(&db_pool.0, &state.username, &state.password) {
-                        Ok(true) => {
-                            // 【修正】ここでCurrentUserリソースにデータを保存する
-                            if let Ok((x, y, words, s, d)) = database::load_user_data(&db_pool.0, &state.username) {
-                                // リソースにメモする
-                                current_user.username = state.username.clone();
-                                current_user.grid_x = x;
-                                current_user.grid_y = y;
-                                current_user.words = words;
-
-                                // 絵文字はリソースなのでそのまま反映OK
-                                emoji_config.s_key = s;
-                                emoji_config.d_key = d;
-
-                                notification.message = format!("Welcome back, {}!", state.username);
-                                notification.is_visible = true;
-                                notification.timer.reset();
-                                next_state.set(GameState::Playing);
-                            } else {
-                                state.error_msg = "Load Error".to_string();
-                            }
-                        },
-                        Ok(false) => state.error_msg = "Invalid User/Pass!".to_string(),
-                        Err(e) => state.error_msg = format!("DB Error: {}", e),
-                    }
-                },
-                AccountMode::Create => {
-                    match database::user_exists(&db_pool.0, &state.username) {
-                        Ok(true) => state.error_msg = "User exists!".to_string(),
-                        Ok(false) => {
-                            if let Err(e) = database::create_user(&db_pool.0, &state.username, &state.password) {
-                                state.error_msg = format!("DB Error: {}", e);
-                            } else {
-                                if let Ok(mut file) = File::create("credentials.txt") {
-                                    let content = format!("ID: {}\nPASS: {}", state.username, state.password);
-                                    let _ = file.write_all(content.as_bytes());
-                                }
-                                
-                                // 作成時は初期値
-                                current_user.username = state.username.clone();
-                                current_user.grid_x = 0;
-                                current_user.grid_y = 0;
-                                current_user.words = vec!["Hello".to_string(), "Help".to_string(), "Yes".to_string(), "No".to_string()];
-                                
-                                notification.message = "Account Created!\nSaved to 'credentials.txt'".to_string();
-                                notification.is_visible = true;
-                                notification.timer.reset();
-                                
-                                next_state.set(GameState::Playing);
-                            }
-                        },
-                        Err(e) => state.error_msg = format!("DB Err: {}", e),
+            match &*storage {
+                #[cfg(not(target_arch = "wasm32"))]
+                Storage::Local(db_pool) => {
+                    match login_local(db_pool, &state) {
+                        Ok(profile) => enter_game(&state, profile, &mut current_user, &mut emoji_config, &mut notification, &mut next_state),
+                        Err(e) => state.error_msg = e,
                     }
                 }
+                // 【新規】サーバー経由。結果 (LoggedIn / LoginFailed) は net_client が受け取る
+                Storage::Remote(sender) => {
+                    let username = state.username.clone();
+                    let password = state.password.clone();
+                    let message = match state.mode {
+                        AccountMode::Login => ClientMessage::Login { username, password },
+                        AccountMode::Create => ClientMessage::Register { username, password },
+                    };
+                    state.error_msg = match sender.send(message) {
+                        Ok(_) => "Connecting...".to_string(),
+                        Err(_) => "Server Error: not connected".to_string(),
+                    };
+                }
             }
         }
     }
@@ -186,6 +160,76 @@ pub fn handle_account_input(
     }
 }
 
+// DB に直接ログイン / アカウント作成する
+#[cfg(not(target_arch = "wasm32"))]
+fn login_local(db_pool: &DbPool, state: &AccountState) -> Result<UserProfile, String> {
+    let pool = &db_pool.0;
+    match state.mode {
+        AccountMode::Login => match database::verify_user(pool, &state.username, &state.password) {
+            Ok(true) => {
+                database::start_session(pool, &state.username).map_err(|e| format!("DB Error: {}", e))?;
+                database::load_profile(pool, &state.username).map_err(|_| "Load Error".to_string())
+            }
+            Ok(false) => Err("Invalid User/Pass!".to_string()),
+            Err(e) => Err(format!("DB Error: {}", e)),
+        },
+        AccountMode::Create => match database::user_exists(pool, &state.username) {
+            Ok(true) => Err("User exists!".to_string()),
+            Ok(false) => {
+                database::create_user(pool, &state.username, &state.password).map_err(|e| format!("DB Error: {}", e))?;
+                database::load_profile(pool, &state.username).map_err(|_| "Load Error".to_string())
+            }
+            Err(e) => Err(format!("DB Err: {}", e)),
+        },
+    }
+}
+
+// 【変更】ログイン成功後の処理（DB 直接・サーバー経由で共通）
+// CurrentUser にセーブデータを移して Playing に進む
+pub fn enter_game(
+    state: &AccountState,
+    profile: UserProfile,
+    current_user: &mut CurrentUser,
+    emoji_config: &mut EmojiConfig,
+    notification: &mut NotificationState,
+    next_state: &mut NextState<GameState>,
+) {
+    current_user.username = state.username.clone();
+    current_user.grid_x = profile.x;
+    current_user.grid_y = profile.y;
+    current_user.words = profile.words;
+    current_user.items = profile.items;
+    current_user.points = profile.points;
+    current_user.quests = profile.quests;
+    current_user.muted = profile.muted;
+    current_user.explored = profile.explored;
+    current_user.explored_counted = profile.explored_counted;
+    current_user.explored_cells = profile.explored_cells;
+    current_user.key_bindings = profile.key_bindings;
+    current_user.waypoints = profile.waypoints;
+    current_user.taken_items = profile.taken_items;
+
+    // 絵文字はリソースなのでそのまま反映OK
+    emoji_config.s_key = profile.s_key;
+    emoji_config.d_key = profile.d_key;
+    emoji_config.unlocked = profile.emojis;
+
+    notification.message = match state.mode {
+        AccountMode::Login => format!("Welcome back, {}!", state.username),
+        AccountMode::Create => {
+            // ブラウザ版ではファイルは作れない（失敗しても無視）
+            if let Ok(mut file) = File::create("credentials.txt") {
+                let content = format!("ID: {}\nPASS: {}", state.username, state.password);
+                let _ = file.write_all(content.as_bytes());
+            }
+            "Account Created!\nSaved to 'credentials.txt'".to_string()
+        }
+    };
+    notification.is_visible = true;
+    notification.timer.reset();
+    next_state.set(GameState::Playing);
+}
+
 pub fn cleanup_account_ui(
     mut commands: Commands,
     query: Query<Entity, With<AccountMenuDisplay>>,
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::constants::TILE_SIZE;
+
+// ==========================================
+// 【新規】プレイヤーの見た目（自分も他のプレイヤーも同じ作り）
+// ワールドに四角の Sprite を置き、名前と絵文字を Text2d の子として付ける。
+// 位置は sync_player_pixel_pos / sync_remote_player_pixel_pos が Transform に入れるので、
+// カメラが遅れて追いかけたりズームしたりしても、体が本当の位置からずれない。
+// 絵文字の子に付けるもの (emoji) で自分 (PlayerEmoji) と他のプレイヤー (RemotePlayerEmoji) を分ける
+// ==========================================
+
+pub fn spawn_avatar(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    name: &str,
+    x: i64,
+    y: i64,
+    z: f32,
+    color: Color,
+    emoji: impl Bundle,
+) -> Entity {
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    let emoji_font = asset_server.load("fonts/NotoEmoji-Bold.ttf");
+
+    commands.spawn((
+        GameEntity,
+        GridPosition { x, y },
+        PixelMotion::at(x, y),
+        Transform::from_xyz(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE, z),
+        Sprite {
+            color,
+            custom_size: Some(Vec2::new(TILE_SIZE * 0.9, TILE_SIZE * 0.9)),
+            ..default()
+        },
+    ))
+    .with_children(|parent| {
+        // 名前
+        parent.spawn((
+            Text2d::new(name),
+            TextFont { font: jp_font, font_size: 14.0, ..default() },
+            TextColor(Color::BLACK),
+            TextLayout::new(JustifyText::Center, LineBreak::NoWrap),
+            Transform::from_xyz(0.0, -TILE_SIZE * 0.7, 10.0),
+        ));
+        // 絵文字（体の上に重ねる）
+        parent.spawn((
+            Text2d::new(""),
+            TextFont { font: emoji_font, font_size: 24.0, ..default() },
+            TextColor(Color::WHITE),
+            TextLayout::new(JustifyText::Center, LineBreak::NoWrap),
+            Transform::from_xyz(0.0, 0.0, 1.0),
+            emoji,
+        ));
+    })
+    .id()
+}
@@ -1,10 +1,15 @@
 use bevy::prelude::*;
-use crate::constants::TILE_SIZE;
+use crate::constants::{AFFINITY_LIMIT, BOT_HEARING_RADIUS, TILE_SIZE};
 use crate::components::*;
-use crate::resources::BotDialogues; // セリフリソースを使う
-use crate::map::{is_bot_spawn, is_obstacle};
+use crate::events::{EmoteEvent, SpeakEvent};
+use crate::resources::{BotDialogues, CurrentUser}; // セリフリソースを使う
+use crate::storage::Storage;
+use crate::map::{bot_seed, is_bot_spawn};
+use crate::quest::dialogue_for;
+use crate::spatial::SpatialIndex;
+use std::collections::HashSet;
+use crate::systems::bot_memory::{flush_bot_memory, greeting_for, unix_now, word_affinity};
 use rand::Rng;
-use rand::seq::SliceRandom; // ランダム選択用
 
 pub fn spawn_visible_bots(
     mut commands: Commands,
@@ -19,6 +24,7 @@ pub fn spawn_visible_bots(
     let Ok(window) = window_query.get_single() else { return };
 
     let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    let emoji_font = asset_server.load("fonts/NotoEmoji-Bold.ttf");
 
     let view_half_width = window.resolution.width() / 2.0 * projection.scale;
     let view_half_height = window.resolution.height() / 2.0 * projection.scale;
@@ -30,7 +36,8 @@ pub fn spawn_visible_bots(
     let start_y = ((cam_pos.y - view_half_height) / TILE_SIZE - buffer).floor() as i64;
     let end_y = ((cam_pos.y + view_half_height) / TILE_SIZE + buffer).ceil() as i64;
 
-    let existing_positions: Vec<(i64, i64)> = existing_bots.iter()
+    // 【変更】Vec::contains だと O(n²) になるので HashSet で引く
+    let existing_positions: HashSet<(i64, i64)> = existing_bots.iter()
         .map(|p| (p.x, p.y))
         .collect();
 
@@ -38,20 +45,16 @@ pub fn spawn_visible_bots(
         for y in start_y..=end_y {
             if is_bot_spawn(x, y) && !existing_positions.contains(&(x, y)) {
                 // セリフをここで決定して渡す
-                let dialogue = if let Some(line) = bot_dialogues.lines.choose(&mut rand::thread_rng()) {
-                    line.clone()
-                } else {
-                    "...".to_string()
-                };
+                let dialogue = dialogue_for(x, y, &bot_dialogues.lines);
 
-                spawn_single_bot(&mut commands, x, y, &jp_font, dialogue);
+                spawn_single_bot(&mut commands, x, y, &jp_font, &emoji_font, dialogue);
             }
         }
     }
 }
 
 // 引数に dialogue を追加
-fn spawn_single_bot(commands: &mut Commands, x: i64, y: i64, font: &Handle<Font>, dialogue: String) {
+fn spawn_single_bot(commands: &mut Commands, x: i64, y: i64, font: &Handle<Font>, emoji_font: &Handle<Font>, dialogue: String) {
     let mut rng = rand::thread_rng();
     let color = Color::srgb(0.0, 0.0, 1.0);
 
@@ -59,12 +62,19 @@ fn spawn_single_bot(commands: &mut Commands, x: i64, y: i64, font: &Handle<Font>
         Bot,
         GameEntity,
         BotSpawnPoint { x, y },
+        // 【新規】今いるマス（空間インデックスに登録される）
+        GridPosition { x, y },
         BotMoveTimer(Timer::from_seconds(rng.gen_range(1.0..3.0), TimerMode::Repeating)),
         
-        // 生成時に決まったセリフを持たせる
+        // 生成時に決まったセリフを持たせる（返事とクエストのヒントに使う）
         BotDialogueText(dialogue),
         // 会話中タイマー
         BotTalking(Timer::from_seconds(0.0, TimerMode::Once)), 
+        // 性格はスポーン地点から決まる（同じ場所のボットは同じ性格）
+        personality_for(x, y),
+        // 記憶は load_bot_memories が後から DB から読み込む
+        BotMemory::default(),
+        BotMemoryPending,
         
         Transform::from_xyz(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE, 0.5), 
         Sprite {
@@ -83,14 +93,34 @@ fn spawn_single_bot(commands: &mut Commands, x: i64, y: i64, font: &Handle<Font>
             BotChatText,
             BotChatTimer(Timer::from_seconds(3.0, TimerMode::Once)),
         ));
+        // 絵文字の返事（プレイヤーの PlayerEmoji と同じく体の上に出す）
+        parent.spawn((
+            Text2d::new(""),
+            TextFont { font: emoji_font.clone(), font_size: 24.0, ..default() },
+            TextColor(Color::WHITE),
+            TextLayout::new(JustifyText::Center, LineBreak::NoWrap),
+            Transform::from_xyz(0.0, 0.0, 1.0),
+            BotEmojiText,
+            BotChatTimer(Timer::from_seconds(3.0, TimerMode::Once)),
+        ));
     });
 }
 
+fn personality_for(x: i64, y: i64) -> BotPersonality {
+    match bot_seed(x, y) % 4 {
+        0 => BotPersonality::Friendly,
+        1 => BotPersonality::Shy,
+        2 => BotPersonality::Grumpy,
+        _ => BotPersonality::Curious,
+    }
+}
+
 pub fn despawn_far_bots(
     mut commands: Commands,
     camera_query: Query<(&GlobalTransform, &OrthographicProjection), With<Camera>>,
     window_query: Query<&Window>,
-    bot_query: Query<(Entity, &Transform), With<Bot>>,
+    mut bot_query: Query<(Entity, &Transform, &BotSpawnPoint, &mut BotMemory, Has<BotMemoryPending>), With<Bot>>,
+    storage: Res<Storage>,
 ) {
     let Ok((cam_transform, projection)) = camera_query.get_single() else { return };
     let Ok(window) = window_query.get_single() else { return };
@@ -98,63 +128,74 @@ pub fn despawn_far_bots(
     let cam_pos = cam_transform.translation();
     let limit_dist = window.resolution.width().max(window.resolution.height()) * projection.scale * 2.0;
 
-    for (entity, transform) in &bot_query {
+    for (entity, transform, spawn_point, mut memory, pending) in &mut bot_query {
         if transform.translation.distance(cam_pos) > limit_dist {
+            // 消える前に記憶を書き込む
+            // 【変更】読み込み前でも捨てずに、DB の内容に足し合わせる
+            flush_bot_memory(&storage, spawn_point, &mut memory, pending);
             commands.entity(entity).despawn_recursive();
         }
     }
 }
 
-pub fn bot_wander_system(
-    time: Res<Time>,
-    // BotTalking も取得
-    mut bot_query: Query<(&mut Transform, &BotSpawnPoint, &mut BotMoveTimer, &mut BotTalking), With<Bot>>,
+// 【新規】ボットの見た目を GridPosition に合わせる（1マスずつ瞬間移動）
+pub fn sync_bot_pixel_pos(
+    mut query: Query<(&GridPosition, &mut Transform), (With<Bot>, Changed<GridPosition>)>,
 ) {
-    let mut rng = rand::thread_rng();
-
-    for (mut transform, spawn_point, mut timer, mut talking) in &mut bot_query {
-        // 会話タイマー進行
-        talking.0.tick(time.delta());
+    for (grid_pos, mut transform) in &mut query {
+        transform.translation.x = grid_pos.x as f32 * TILE_SIZE;
+        transform.translation.y = grid_pos.y as f32 * TILE_SIZE;
+    }
+}
 
-        // 話している最中は移動処理をスキップ
-        if !talking.0.finished() {
-            continue;
-        }
+// ==========================================
+// 【新規】言葉への返事
+// 挨拶には付き合いの長さに応じた返事、それ以外はそのボットのセリフ (BotDialogueText) で返す
+// 【変更】クエストの方角のヒントはこのセリフから作るので、プレイヤーが聞くのもこのセリフにする
+// ==========================================
+pub fn bot_respond_to_words(
+    mut speak_events: EventReader<SpeakEvent>,
+    current_user: Res<CurrentUser>,
+    index: Res<SpatialIndex>,
+    mut bot_query: Query<(&Children, &mut BotTalking, &BotDialogueText, &mut BotMemory), With<Bot>>,
+    mut bot_text_query: Query<(&mut Text2d, &mut BotChatTimer), With<BotChatText>>,
+) {
+    let now = unix_now();
 
-        timer.0.tick(time.delta());
+    for event in speak_events.read() {
+        // 【変更】半径 BOT_HEARING_RADIUS マス以内（円形判定）
+        for entity in index.within_radius((event.x, event.y), BOT_HEARING_RADIUS) {
+            let Ok((children, mut talking, dialogue, mut memory)) = bot_query.get_mut(entity) else { continue };
 
-        if timer.0.finished() {
-            let direction = rng.gen_range(0..4);
-            let (dx, dy) = match direction {
-                0 => (0, 1),
-                1 => (0, -1),
-                2 => (-1, 0),
-                3 => (1, 0),
-                _ => (0, 0),
+            let entry = memory.players.entry(current_user.username.clone()).or_default();
+            let response = if event.word == "Hello" {
+                let greeting = greeting_for(entry, &current_user.username, now);
+                entry.times_greeted += 1;
+                greeting
+            } else {
+                dialogue.0.clone()
             };
+            entry.affinity = (entry.affinity + word_affinity(&event.word)).clamp(-AFFINITY_LIMIT, AFFINITY_LIMIT);
+            entry.last_seen = now;
+            entry.dirty = true;
 
-            let current_grid_x = (transform.translation.x / TILE_SIZE).round() as i64;
-            let current_grid_y = (transform.translation.y / TILE_SIZE).round() as i64;
-
-            let next_x = current_grid_x + dx;
-            let next_y = current_grid_y + dy;
+            talking.0.set_duration(std::time::Duration::from_secs(3));
+            talking.0.reset();
 
-            if (next_x - spawn_point.x).abs() <= 5 && (next_y - spawn_point.y).abs() <= 5 {
-                if !is_obstacle(next_x, next_y) {
-                    transform.translation.x = next_x as f32 * TILE_SIZE;
-                    transform.translation.y = next_y as f32 * TILE_SIZE;
+            for &child in children.iter() {
+                if let Ok((mut text, mut timer)) = bot_text_query.get_mut(child) {
+                    text.0 = response.clone();
+                    timer.0.reset();
                 }
             }
-            
-            timer.0.set_duration(std::time::Duration::from_secs_f32(rng.gen_range(0.5..2.0)));
-            timer.0.reset();
         }
     }
 }
 
 pub fn update_bot_chat(
     time: Res<Time>,
-    mut query: Query<(&mut Text2d, &mut BotChatTimer), With<BotChatText>>,
+    // セリフ(BotChatText)と絵文字(BotEmojiText)の両方が BotChatTimer で消える
+    mut query: Query<(&mut Text2d, &mut BotChatTimer)>,
 ) {
     for (mut text, mut timer) in &mut query {
         if !text.0.is_empty() {
@@ -164,4 +205,129 @@ pub fn update_bot_chat(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// ==========================================
+// 【新規】絵文字への反応
+// ==========================================
+
+// 絵文字の印象
+#[derive(Clone, Copy, PartialEq)]
+enum EmoteTone {
+    Positive,
+    Negative,
+    Sad,
+    Neutral,
+}
+
+const POSITIVE_EMOJIS: [&str; 5] = ["👍", "😁", "😇", "❤️", "🔥"];
+const NEGATIVE_EMOJIS: [&str; 6] = ["😡", "🤮", "💩", "💀", "💢", "👾"];
+const SAD_EMOJIS: [&str; 3] = ["😭", "💔", "💦"];
+
+fn emote_tone(emoji: &str) -> EmoteTone {
+    if POSITIVE_EMOJIS.contains(&emoji) {
+        EmoteTone::Positive
+    } else if NEGATIVE_EMOJIS.contains(&emoji) {
+        EmoteTone::Negative
+    } else if SAD_EMOJIS.contains(&emoji) {
+        EmoteTone::Sad
+    } else {
+        EmoteTone::Neutral
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BotMotion {
+    Stay,
+    Approach,
+    WalkAway,
+}
+
+struct BotReaction {
+    reply: &'static str,
+    motion: BotMotion,
+    affinity: i32,
+}
+
+// 性格 × 絵文字の印象 → 反応
+// 好感度が十分に高い/低いときは性格より好感度を優先する
+fn react(personality: BotPersonality, tone: EmoteTone, affinity: i32) -> BotReaction {
+    use BotMotion::*;
+    use EmoteTone::*;
+
+    let r = |reply, motion, affinity| BotReaction { reply, motion, affinity };
+
+    if affinity >= 3 && tone == Positive {
+        return r("❤️", Approach, 1);
+    }
+    if affinity <= -3 {
+        return r("💢", WalkAway, if tone == Positive { 1 } else { 0 });
+    }
+
+    match (personality, tone) {
+        (BotPersonality::Friendly, Positive) => r("😁", Approach, 1),
+        (BotPersonality::Friendly, Sad) => r("❤️", Approach, 1),
+        (BotPersonality::Friendly, Negative) => r("😭", WalkAway, -1),
+        (BotPersonality::Friendly, Neutral) => r("😁", Stay, 0),
+
+        (BotPersonality::Shy, Positive) => r("😇", Stay, 1),
+        (BotPersonality::Shy, Sad) => r("💦", Stay, 0),
+        (BotPersonality::Shy, Negative) => r("😭", WalkAway, -2),
+        (BotPersonality::Shy, Neutral) => r("👀", WalkAway, 0),
+
+        (BotPersonality::Grumpy, Positive) => r("🤔", Stay, 1),
+        (BotPersonality::Grumpy, Sad) => r("💤", Stay, 0),
+        (BotPersonality::Grumpy, Negative) => r("😡", Approach, -1),
+        (BotPersonality::Grumpy, Neutral) => r("💢", Stay, 0),
+
+        (BotPersonality::Curious, Positive) => r("👀", Approach, 1),
+        (BotPersonality::Curious, Sad) => r("🤔", Approach, 0),
+        (BotPersonality::Curious, Negative) => r("🤔", Stay, -1),
+        (BotPersonality::Curious, Neutral) => r("👀", Approach, 0),
+    }
+}
+
+pub fn bot_react_to_emotes(
+    mut commands: Commands,
+    mut emote_events: EventReader<EmoteEvent>,
+    current_user: Res<CurrentUser>,
+    index: Res<SpatialIndex>,
+    mut bot_query: Query<(&Children, &BotPersonality, &mut BotMemory), With<Bot>>,
+    mut emoji_text_query: Query<(&mut Text2d, &mut BotChatTimer), With<BotEmojiText>>,
+) {
+    let now = unix_now();
+
+    for event in emote_events.read() {
+        let tone = emote_tone(&event.emoji);
+
+        for entity in index.within_radius((event.x, event.y), BOT_HEARING_RADIUS) {
+            let Ok((children, personality, mut memory)) = bot_query.get_mut(entity) else { continue };
+
+            // 絵文字の印象は記憶（好感度）として残る
+            let entry = memory.players.entry(current_user.username.clone()).or_default();
+            let reaction = react(*personality, tone, entry.affinity);
+            entry.affinity = (entry.affinity + reaction.affinity).clamp(-AFFINITY_LIMIT, AFFINITY_LIMIT);
+            entry.last_seen = now;
+            entry.dirty = true;
+
+            for &child in children.iter() {
+                if let Ok((mut text, mut timer)) = emoji_text_query.get_mut(child) {
+                    text.0 = reaction.reply.to_string();
+                    timer.0.reset();
+                }
+            }
+
+            match reaction.motion {
+                BotMotion::Stay => {}
+                BotMotion::Approach | BotMotion::WalkAway => {
+                    commands.entity(entity).insert(BotIntent {
+                        target_x: event.x,
+                        target_y: event.y,
+                        approach: reaction.motion == BotMotion::Approach,
+                        steps_left: 3,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::net::ClientMessage;
+use crate::storage::Storage;
+use crate::constants::AFFINITY_LIMIT;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::database;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 最後に会ってからこの秒数が経っていたら「おかえり」と言う
+const WELCOME_BACK_SECS: i64 = 10 * 60;
+
+// 1フレームで記憶を読み込むボットの最大数（DBアクセスでカクつかないように）
+const MAX_LOADS_PER_FRAME: usize = 4;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// ブラウザでは SystemTime が使えないので JS の時計を使う
+#[cfg(target_arch = "wasm32")]
+pub fn unix_now() -> i64 {
+    (js_sys::Date::now() / 1000.0) as i64
+}
+
+// 挨拶 ("Hello") への返事を、これまでの付き合いから決める
+// times_greeted / last_seen は更新前の値を渡すこと
+pub fn greeting_for(memory: &PlayerMemory, username: &str, now: i64) -> String {
+    if memory.times_greeted == 0 {
+        return "Hello".to_string();
+    }
+    if memory.affinity <= -3 {
+        return "...Hello.".to_string();
+    }
+
+    let is_regular = memory.times_greeted >= 5;
+    let long_time_no_see = memory.last_seen > 0 && now - memory.last_seen >= WELCOME_BACK_SECS;
+
+    match (long_time_no_see, is_regular) {
+        (true, true) => format!("Welcome back, {}!", username),
+        (true, false) => "Welcome back!".to_string(),
+        (false, true) => format!("Hello, {}!", username),
+        (false, false) => "Hello again!".to_string(),
+    }
+}
+
+// 言葉ごとの好感度の変化
+pub fn word_affinity(word: &str) -> i32 {
+    match word {
+        "Hello" | "Yes" => 1,
+        "No" => -1,
+        _ => 0,
+    }
+}
+
+// ==========================================
+// 記憶の遅延読み込み
+// 役割: ストリームインしたボット (BotMemoryPending) の記憶を DB から読み込む。
+// スポーン直後に会話した分は消さずに足し合わせる。
+// 【変更】サーバー経由の場合は問い合わせだけ送り、返事は net_client が merge_bot_memory_rows で反映する
+// ==========================================
+// ブラウザ版は DB 直接の分岐がないので使わない引数がある
+#[cfg_attr(target_arch = "wasm32", allow(unused_variables, unused_mut))]
+pub fn load_bot_memories(
+    mut commands: Commands,
+    storage: Res<Storage>,
+    mut query: Query<(Entity, &BotSpawnPoint, &mut BotMemory), (With<BotMemoryPending>, Without<BotMemoryRequested>)>,
+) {
+    for (entity, spawn_point, mut memory) in query.iter_mut().take(MAX_LOADS_PER_FRAME) {
+        match &*storage {
+            #[cfg(not(target_arch = "wasm32"))]
+            Storage::Local(db_pool) => {
+                match database::load_bot_memories(&db_pool.0, spawn_point.x, spawn_point.y) {
+                    Ok(rows) => merge_bot_memory_rows(&mut memory, rows),
+                    // 失敗しても毎フレーム再試行はしない（このボットは初対面として扱う）
+                    Err(e) => eprintln!("Bot Memory Load Error: {}", e),
+                }
+                commands.entity(entity).remove::<BotMemoryPending>();
+            }
+            Storage::Remote(sender) => {
+                let _ = sender.send(ClientMessage::LoadBotMemories { bot_x: spawn_point.x, bot_y: spawn_point.y });
+                commands.entity(entity).insert(BotMemoryRequested);
+            }
+        }
+    }
+}
+
+// DB の記憶 (username, times_greeted, last_seen, affinity) を足し合わせる
+pub fn merge_bot_memory_rows(memory: &mut BotMemory, rows: Vec<(String, i32, i64, i32)>) {
+    for (username, times_greeted, last_seen, affinity) in rows {
+        let entry = memory.players.entry(username).or_default();
+        entry.times_greeted += times_greeted;
+        entry.affinity = (entry.affinity + affinity).clamp(-AFFINITY_LIMIT, AFFINITY_LIMIT);
+        entry.last_seen = entry.last_seen.max(last_seen);
+    }
+}
+
+// 未保存の記憶を DB に書き込む
+// 【変更】読み込み前 (pending) の記憶は DB の内容に足し合わせる（上書きすると今までの記憶が消える）。
+// 足し合わせた後に読み込むと二重に数えるので、pending で呼ぶのはボットが消える直前だけにする
+pub fn flush_bot_memory(storage: &Storage, spawn_point: &BotSpawnPoint, memory: &mut BotMemory, pending: bool) {
+    for (username, entry) in memory.players.iter_mut().filter(|(_, m)| m.dirty) {
+        let save = if pending { Storage::add_bot_memory } else { Storage::save_bot_memory };
+        match save(
+            storage,
+            spawn_point.x,
+            spawn_point.y,
+            username,
+            entry.times_greeted,
+            entry.last_seen,
+            entry.affinity,
+        ) {
+            Ok(_) => entry.dirty = false,
+            Err(e) => eprintln!("Bot Memory Save Error: {}", e),
+        }
+    }
+}
+
+// 定期的に未保存の記憶をまとめて書き込む
+// (範囲外に出て消えるボットは despawn_far_bots が消す直前に書き込む)
+pub fn save_bot_memories(
+    time: Res<Time>,
+    mut save_timer: ResMut<BotMemorySaveTimer>,
+    storage: Res<Storage>,
+    mut query: Query<(&BotSpawnPoint, &mut BotMemory), Without<BotMemoryPending>>,
+) {
+    save_timer.0.tick(time.delta());
+    if !save_timer.0.just_finished() {
+        return;
+    }
+
+    for (spawn_point, mut memory) in &mut query {
+        if memory.players.values().any(|m| m.dirty) {
+            flush_bot_memory(&storage, spawn_point, &mut memory, false);
+        }
+    }
+}
+
+// 【新規】終了するときに未保存の記憶を書き込む（BotMemorySaveTimer を待たない）
+// AppExit はどのスケジュールで送られても Last までには届く
+pub fn flush_bot_memories_on_exit(
+    mut exit_events: EventReader<AppExit>,
+    storage: Res<Storage>,
+    mut query: Query<(&BotSpawnPoint, &mut BotMemory, Has<BotMemoryPending>)>,
+) {
+    if exit_events.read().count() == 0 {
+        return;
+    }
+    for (spawn_point, mut memory, pending) in &mut query {
+        flush_bot_memory(&storage, spawn_point, &mut memory, pending);
+    }
+}
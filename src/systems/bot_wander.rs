@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::map::is_obstacle;
+use crate::spatial::SpatialIndex;
+use rand::Rng;
+
+// ==========================================
+// ボットの徘徊
+// 描画に関係しないのでクライアントとサーバー (evol-server) の両方で使う
+// ==========================================
+pub fn bot_wander_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    // BotTalking も取得
+    // 【新規】BotIntent があれば徘徊の代わりに目標基準で動く
+    // 【変更】位置は GridPosition で持ち、見た目は sync_bot_pixel_pos が合わせる
+    mut bot_query: Query<(Entity, &mut GridPosition, &BotSpawnPoint, &mut BotMoveTimer, &mut BotTalking, Option<&mut BotIntent>), With<Bot>>,
+    mut index: ResMut<SpatialIndex>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for (entity, mut grid_pos, spawn_point, mut timer, mut talking, intent) in &mut bot_query {
+        // 会話タイマー進行
+        talking.0.tick(time.delta());
+
+        // 話している最中は移動処理をスキップ
+        if !talking.0.finished() {
+            continue;
+        }
+
+        timer.0.tick(time.delta());
+
+        if timer.0.finished() {
+            let (current_grid_x, current_grid_y) = (grid_pos.x, grid_pos.y);
+
+            // 候補の方向（先頭から順に試す）
+            let candidates: Vec<(i64, i64)> = match intent {
+                Some(mut intent) => {
+                    let candidates = intent_directions(current_grid_x, current_grid_y, &intent);
+                    intent.steps_left = intent.steps_left.saturating_sub(1);
+                    if intent.steps_left == 0 || candidates.is_empty() {
+                        commands.entity(entity).remove::<BotIntent>();
+                    }
+                    candidates
+                }
+                None => {
+                    let direction = rng.gen_range(0..4);
+                    let dir = match direction {
+                        0 => (0, 1),
+                        1 => (0, -1),
+                        2 => (-1, 0),
+                        3 => (1, 0),
+                        _ => (0, 0),
+                    };
+                    vec![dir]
+                }
+            };
+
+            for (dx, dy) in candidates {
+                let next_x = current_grid_x + dx;
+                let next_y = current_grid_y + dy;
+
+                // 【新規】他のボットやプレイヤーがいるマスには入らない
+                if (next_x - spawn_point.x).abs() <= 5
+                    && (next_y - spawn_point.y).abs() <= 5
+                    && !is_obstacle(next_x, next_y)
+                    && !index.is_occupied((next_x, next_y))
+                {
+                    grid_pos.x = next_x;
+                    grid_pos.y = next_y;
+                    // 同じフレームで動く他のボットと重ならないよう、すぐにインデックスへ反映する
+                    index.set(entity, (next_x, next_y));
+                    break;
+                }
+            }
+            
+            timer.0.set_duration(std::time::Duration::from_secs_f32(rng.gen_range(0.5..2.0)));
+            timer.0.reset();
+        }
+    }
+}
+
+// 目標に近づく / 離れる方向を優先順に返す（距離の大きい軸から）
+// 近づく場合は隣のマスに着いたら空を返す（重ならないように止まる）
+fn intent_directions(x: i64, y: i64, intent: &BotIntent) -> Vec<(i64, i64)> {
+    let dx = intent.target_x - x;
+    let dy = intent.target_y - y;
+
+    if intent.approach && dx.abs().max(dy.abs()) <= 1 {
+        return Vec::new();
+    }
+
+    let sign = if intent.approach { 1 } else { -1 };
+    // 真上にいる場合などで signum が 0 になる軸は除外する
+    let step_x = (dx.signum() * sign, 0);
+    let step_y = (0, dy.signum() * sign);
+    let mut dirs = if dx.abs() >= dy.abs() { vec![step_x, step_y] } else { vec![step_y, step_x] };
+    dirs.retain(|&d| d != (0, 0));
+    if dirs.is_empty() {
+        // 同じマスから離れる場合はとりあえず上へ
+        dirs.push((0, 1));
+    }
+    dirs
+}
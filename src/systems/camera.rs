@@ -1,59 +1,113 @@
 use bevy::prelude::*;
-use crate::constants::{TILE_SIZE, GRID_COLOR};
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use crate::constants::{TILE_SIZE, CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM, CAMERA_ZOOM_STEP};
 use crate::components::Player;
+use crate::controls::{Action, ActionState};
+use crate::systems::minimap::MapState;
+
+// 【新規】カメラの追従とズームの速さ（1 秒あたり、残りの距離のどれだけ近づくか）
+const FOLLOW_SPEED: f32 = 8.0;
+const ZOOM_SPEED: f32 = 10.0;
+// これより離れたら（テレポートなど）追いかけずにすぐ合わせる (マス数)
+const FOLLOW_SNAP_DISTANCE: f32 = 8.0;
+// ホイールがピクセル単位のとき、1 目盛りとみなす量
+const WHEEL_PIXELS_PER_LINE: f32 = 100.0;
+
+// 【新規】目標のズーム (projection.scale はここへ少しずつ近づく)
+#[derive(Resource)]
+pub struct CameraZoom {
+    pub target: f32,
+}
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        CameraZoom { target: 1.0 }
+    }
+}
+
+impl CameraZoom {
+    // factor > 1 で引く（広く映す）
+    fn scale_by(&mut self, factor: f32) {
+        self.target = (self.target * factor).clamp(CAMERA_MIN_ZOOM, CAMERA_MAX_ZOOM);
+    }
+}
+
+// 残りの距離を 1 秒に speed の割合で縮める（フレームレートによらない）
+fn smoothing(speed: f32, dt: f32) -> f32 {
+    1.0 - (-speed * dt).exp()
+}
+
+// 【新規】ズームの入力（キー / ホイール / 2 本指のピンチ）
+// ワールドマップを開いている間はマップのズームになる（キーはワールドマップが止める）
+pub fn handle_camera_zoom(
+    actions: Res<ActionState>,
+    mut wheel_events: EventReader<MouseWheel>,
+    touches: Res<Touches>,
+    // UI（ボタン・ミニマップ）の上での操作はズームにしない
+    interaction_query: Query<&Interaction>,
+    map_state: Res<MapState>,
+    mut zoom: ResMut<CameraZoom>,
+) {
+    if actions.just_pressed(Action::ZoomIn) {
+        zoom.scale_by(1.0 / CAMERA_ZOOM_STEP);
+    }
+    if actions.just_pressed(Action::ZoomOut) {
+        zoom.scale_by(CAMERA_ZOOM_STEP);
+    }
+
+    let lines: f32 = wheel_events
+        .read()
+        .map(|e| match e.unit {
+            MouseScrollUnit::Line => e.y,
+            MouseScrollUnit::Pixel => e.y / WHEEL_PIXELS_PER_LINE,
+        })
+        .sum();
+    if map_state.world_open || interaction_query.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+    // 上に回すと寄る
+    if lines != 0.0 {
+        zoom.scale_by(CAMERA_ZOOM_STEP.powf(-lines));
+    }
+
+    // 2 本指の間隔が広がった分だけ寄る
+    let fingers: Vec<_> = touches.iter().collect();
+    if let [a, b] = fingers[..] {
+        let before = a.previous_position().distance(b.previous_position());
+        let after = a.position().distance(b.position());
+        if before > 0.0 && after > 0.0 {
+            zoom.scale_by(before / after);
+        }
+    }
+}
 
 // カメラ追従
+// 【変更】プレイヤーにすぐ合わせず、なめらかに追いかける。ズームも目標へ少しずつ近づける
 pub fn camera_follow(
+    time: Res<Time>,
+    zoom: Res<CameraZoom>,
     player_query: Query<&Transform, (With<Player>, Without<Camera>)>,
-    mut camera_query: Query<&mut Transform, With<Camera>>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera>>,
 ) {
+    let Ok((mut camera_transform, mut projection)) = camera_query.get_single_mut() else { return };
+    let dt = time.delta_secs();
+
     if let Ok(player_transform) = player_query.get_single() {
-        if let Ok(mut camera_transform) = camera_query.get_single_mut() {
-            // X, Yはプレイヤーに合わせる（Zはそのまま）
-            camera_transform.translation.x = player_transform.translation.x;
-            camera_transform.translation.y = player_transform.translation.y;
-        }
+        // X, Yはプレイヤーに合わせる（Zはそのまま）
+        let target = player_transform.translation.truncate();
+        let current = camera_transform.translation.truncate();
+        let next = if current.distance(target) > FOLLOW_SNAP_DISTANCE * TILE_SIZE {
+            target
+        } else {
+            current.lerp(target, smoothing(FOLLOW_SPEED, dt))
+        };
+        camera_transform.translation.x = next.x;
+        camera_transform.translation.y = next.y;
     }
-}
 
-// グリッド描画（Gizmosを使用）
-pub fn draw_grid_optimized(
-    mut gizmos: Gizmos,
-    camera_query: Query<(&Camera, &GlobalTransform, &OrthographicProjection)>,
-    window_query: Query<&Window>,
-) {
-    // カメラやウィンドウが取得できない場合は描画しない
-    let Ok((_, cam_transform, projection)) = camera_query.get_single() else { return };
-    let Ok(window) = window_query.get_single() else { return };
-
-    let view_half_width = window.resolution.width() / 2.0 * projection.scale;
-    let view_half_height = window.resolution.height() / 2.0 * projection.scale;
-    let cam_pos = cam_transform.translation();
-
-    // 画面内に映る範囲だけ計算
-    let start_x = ((cam_pos.x - view_half_width) / TILE_SIZE).floor() as i64;
-    let end_x = ((cam_pos.x + view_half_width) / TILE_SIZE).ceil() as i64;
-    let start_y = ((cam_pos.y - view_half_height) / TILE_SIZE).floor() as i64;
-    let end_y = ((cam_pos.y + view_half_height) / TILE_SIZE).ceil() as i64;
-
-    // グリッドを奥(-10.0)に描画
-    let grid_z = -10.0;
-
-    // 縦線
-    for x in start_x..=end_x {
-        gizmos.line(
-            Vec3::new(x as f32 * TILE_SIZE - (TILE_SIZE / 2.0), (start_y as f32 - 1.0) * TILE_SIZE, grid_z),
-            Vec3::new(x as f32 * TILE_SIZE - (TILE_SIZE / 2.0), (end_y as f32 + 1.0) * TILE_SIZE, grid_z),
-            GRID_COLOR,
-        );
-    }
-
-    // 横線
-    for y in start_y..=end_y {
-        gizmos.line(
-            Vec3::new((start_x as f32 - 1.0) * TILE_SIZE, y as f32 * TILE_SIZE - (TILE_SIZE / 2.0), grid_z),
-            Vec3::new((end_x as f32 + 1.0) * TILE_SIZE, y as f32 * TILE_SIZE - (TILE_SIZE / 2.0), grid_z),
-            GRID_COLOR,
-        );
-    }
-}
\ No newline at end of file
+    if projection.scale != zoom.target {
+        let next = projection.scale + (zoom.target - projection.scale) * smoothing(ZOOM_SPEED, dt);
+        // 目標にほぼ届いたら合わせる（いつまでも変更扱いにしない）
+        projection.scale = if (next - zoom.target).abs() < 0.001 { zoom.target } else { next };
+    }
+}
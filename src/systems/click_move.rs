@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::constants::TILE_SIZE;
+use crate::pathfind::find_path;
+use crate::systems::minimap::MapState;
+
+// ==========================================
+// 【新規】クリック / タップで移動
+// 押したマスまでの経路を A* で探して MovePath に入れる（歩くのは move_player_tick）。
+// 経路は足あととして地面に描く
+// ==========================================
+
+const TRAIL_COLOR: Color = Color::srgba(0.2, 0.5, 1.0, 0.8);
+
+pub fn handle_click_to_move(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    window_query: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    // UI（ボタン・ミニマップ）の上を押したときは動かない
+    interaction_query: Query<&Interaction>,
+    emoji_state: Res<EmojiSelectState>,
+    chat_menu_state: Res<ChatMenuState>,
+    map_state: Res<MapState>,
+    mut move_path: ResMut<MovePath>,
+    mut notification: ResMut<NotificationState>,
+    player_query: Query<&GridPosition, With<Player>>,
+) {
+    if emoji_state.is_open || chat_menu_state.is_open || map_state.world_open {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else { return };
+    let screen_pos = if mouse_input.just_pressed(MouseButton::Left) {
+        window.cursor_position()
+    } else {
+        touches.iter_just_pressed().next().map(|t| t.position())
+    };
+    let Some(screen_pos) = screen_pos else { return };
+    if interaction_query.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+
+    let Ok((camera, camera_transform)) = camera_query.get_single() else { return };
+    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, screen_pos) else { return };
+    let goal = ((world_pos.x / TILE_SIZE).round() as i64, (world_pos.y / TILE_SIZE).round() as i64);
+    let Ok(pos) = player_query.get_single() else { return };
+
+    match find_path((pos.x, pos.y), goal) {
+        Some(path) => move_path.0 = path.into(),
+        None => {
+            move_path.0.clear();
+            if goal != (pos.x, pos.y) {
+                notification.message = "Can't get there!".to_string();
+                notification.is_visible = true;
+                notification.timer.reset();
+            }
+        }
+    }
+}
+
+// 残りの経路を点で、行き先を輪で描く
+pub fn draw_move_path(mut gizmos: Gizmos, move_path: Res<MovePath>) {
+    for (i, &(x, y)) in move_path.0.iter().enumerate() {
+        let center = Vec2::new(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE);
+        if i + 1 == move_path.0.len() {
+            gizmos.circle_2d(center, TILE_SIZE * 0.35, TRAIL_COLOR);
+        } else {
+            gizmos.circle_2d(center, TILE_SIZE * 0.1, TRAIL_COLOR);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+use bevy::asset::AssetLoadFailedEvent;
+use crate::content::{parse_emoji_list, parse_item_table, ContentText};
+use crate::resources::*;
+
+// ==========================================
+// コンテンツの反映システム
+// 役割: ContentText の読み込み・再読み込み結果を各リソースに反映する。
+// 既にスポーンしているボットのセリフ（返事とクエストのヒント）は変えない（次にスポーンするボットから反映）
+// ==========================================
+pub fn apply_content_updates(
+    mut asset_events: EventReader<AssetEvent<ContentText>>,
+    mut failed_events: EventReader<AssetLoadFailedEvent<ContentText>>,
+    contents: Res<Assets<ContentText>>,
+    handles: Option<Res<ContentHandles>>,
+    mut bot_dialogues: ResMut<BotDialogues>,
+    mut emoji_catalog: ResMut<EmojiCatalog>,
+    mut item_table: ResMut<ItemTable>,
+    mut notification: ResMut<NotificationState>,
+) {
+    let Some(handles) = handles else { return };
+
+    for event in asset_events.read() {
+        // Added = 初回読み込み, Modified = ファイル変更による再読み込み
+        let (id, is_reload) = match event {
+            AssetEvent::Added { id } => (*id, false),
+            AssetEvent::Modified { id } => (*id, true),
+            _ => continue,
+        };
+        let Some(content) = contents.get(id) else { continue };
+
+        let result = if id == handles.bot_dialogues.id() {
+            if content.lines.is_empty() {
+                Err("bot_dialogues.txt: no dialogue lines".to_string())
+            } else {
+                bot_dialogues.lines = content.lines.clone();
+                Ok(format!("bot_dialogues.txt: {} lines", bot_dialogues.lines.len()))
+            }
+        } else if id == handles.emoji_list.id() {
+            match parse_emoji_list(&content.lines) {
+                Ok(emojis) => {
+                    emoji_catalog.emojis = emojis;
+                    Ok(format!("emoji_list.txt: {} emojis", emoji_catalog.emojis.len()))
+                }
+                Err(e) => Err(format!("emoji_list.txt: {}", e)),
+            }
+        } else if id == handles.item_table.id() {
+            match parse_item_table(&content.lines) {
+                Ok(items) => {
+                    item_table.items = items;
+                    Ok(format!("item_table.txt: {} items", item_table.items.len()))
+                }
+                Err(e) => Err(format!("item_table.txt: {}", e)),
+            }
+        } else {
+            continue;
+        };
+
+        match result {
+            Ok(summary) => {
+                println!("Loaded {}", summary);
+                if is_reload {
+                    notification.message = format!("Reloaded {}", summary);
+                    notification.is_visible = true;
+                    notification.timer.reset();
+                }
+            }
+            // 解析に失敗した場合は前の内容を維持する
+            Err(e) => {
+                eprintln!("Content Error: {}", e);
+                notification.message = format!("Content Error:\n{}", e);
+                notification.is_visible = true;
+                notification.timer.reset();
+            }
+        }
+    }
+
+    for event in failed_events.read() {
+        eprintln!("Failed to load {}: {}", event.path, event.error);
+
+        if event.id == handles.bot_dialogues.id() && bot_dialogues.lines.is_empty() {
+            bot_dialogues.lines.push("No dialogues found.".to_string());
+        }
+
+        notification.message = format!("Content Error:\n{}", event.path);
+        notification.is_visible = true;
+        notification.timer.reset();
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::explore::{milestone_crossed, ExploredMap, EXPLORE_RADIUS};
+use crate::storage::Storage;
+
+// ==========================================
+// 【新規】探索済みの場所を記録する
+// 歩くたびに周りを探索済みにし、定期的に保存する。
+// 探索したマス数が区切りを超えたら実績として通知する
+// ==========================================
+
+// ログインで読んだ分を Explored に移す
+pub fn setup_explored(mut current_user: ResMut<CurrentUser>, mut explored: ResMut<Explored>) {
+    let chunks = std::mem::take(&mut current_user.explored);
+    let counted = std::mem::take(&mut current_user.explored_counted);
+    explored.0 = ExploredMap::from_chunks(chunks, counted, current_user.explored_cells);
+}
+
+pub fn explore_around_player(
+    mut explored: ResMut<Explored>,
+    mut current_user: ResMut<CurrentUser>,
+    mut notification: ResMut<NotificationState>,
+    player_query: Query<&GridPosition, (With<Player>, Changed<GridPosition>)>,
+) {
+    let Ok(pos) = player_query.get_single() else { return };
+    let before = explored.0.cells;
+    if explored.0.reveal(pos.x, pos.y, EXPLORE_RADIUS) == 0 {
+        return;
+    }
+    current_user.explored_cells = explored.0.cells;
+
+    if let Some(milestone) = milestone_crossed(before, explored.0.cells) {
+        notification.message = format!("Explorer!\n{} cells explored", milestone);
+        notification.is_visible = true;
+        notification.timer.reset();
+    }
+}
+
+pub fn save_explored(
+    time: Res<Time>,
+    mut save_timer: ResMut<ExploreSaveTimer>,
+    storage: Res<Storage>,
+    current_user: Res<CurrentUser>,
+    mut explored: ResMut<Explored>,
+) {
+    save_timer.0.tick(time.delta());
+    if !save_timer.0.just_finished() {
+        return;
+    }
+
+    let chunks = explored.0.take_dirty();
+    let counted = explored.0.take_forgotten();
+    if chunks.is_empty() && counted.is_empty() {
+        return;
+    }
+    if let Err(e) = storage.save_explored(&current_user.username, &chunks, &counted, explored.0.cells) {
+        eprintln!("Explored Save Error: {}", e);
+    }
+}
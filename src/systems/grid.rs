@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{AlphaMode2d, Material2d};
+use crate::constants::{TILE_SIZE, GRID_COLOR};
+use crate::components::{GameEntity, GridPosition, Player};
+use crate::resources::Waypoints;
+
+// ==========================================
+// 【新規】グリッドの描画（Gizmos の線の代わり）
+// 画面を覆う四角を 1 枚置き、シェーダー (assets/shaders/grid.wgsl) が線を描く。
+// シェーダーに渡す座標は、カメラの近くのマス (origin) からのマス数にしておく（浮動原点）。
+// 【変更】origin はプレイヤーのマス (i64) と、そこからのカメラの小さなずれ (f32) から求める。
+// カメラの座標 (f32) そのものからは求めないので、ワールドの座標が大きくなってもマスがずれない。
+// 原点と保存したウェイポイントのマスは色を塗る
+// ==========================================
+
+// 線の間隔がこれより狭くなったら 2, 4, 8... マスごとに間引く (画面上のピクセル)
+const GRID_MIN_SPACING: f32 = 12.0;
+// グリッドを奥(-10.0)に描画
+const GRID_Z: f32 = -10.0;
+// 色を塗れるマスの数 (シェーダーの配列の長さと同じ)
+pub const MAX_GRID_HIGHLIGHTS: usize = 16;
+
+// ミニマップの印と同じ色（薄く塗る）
+const ORIGIN_HIGHLIGHT: Color = Color::srgba(0.86, 0.0, 0.0, 0.25);
+const WAYPOINT_HIGHLIGHT: Color = Color::srgba(0.78, 0.0, 0.78, 0.25);
+
+// シェーダーの GridParams と同じ並び
+// (ShaderType の derive が作る確認用の関数が未使用の警告になるので、モジュールごと許可する)
+#[allow(dead_code)]
+mod params {
+    use bevy::prelude::*;
+    use bevy::render::render_resource::ShaderType;
+    use super::MAX_GRID_HIGHLIGHTS;
+
+    #[derive(ShaderType, Clone, Copy, Debug, Default, PartialEq)]
+    pub struct GridParams {
+        pub color: Vec4,
+        // カメラの位置と映っている広さ (origin からのマス数。マスの境目が整数)
+        pub center: Vec2,
+        pub size: Vec2,
+        // 線の間隔 (マス数)
+        pub step: f32,
+        pub highlight_count: u32,
+        // xy: 塗るマス (origin から)
+        pub highlights: [Vec4; MAX_GRID_HIGHLIGHTS],
+        pub highlight_colors: [Vec4; MAX_GRID_HIGHLIGHTS],
+    }
+}
+use params::GridParams;
+
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct GridMaterial {
+    #[uniform(0)]
+    params: GridParams,
+}
+
+impl Material2d for GridMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/grid.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+#[derive(Component)]
+pub struct GridQuad;
+
+pub fn setup_grid(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial2d(materials.add(GridMaterial { params: GridParams::default() })),
+        Transform::from_xyz(0.0, 0.0, GRID_Z),
+        GridQuad,
+        GameEntity,
+    ));
+}
+
+// カメラに合わせて四角を動かし、シェーダーに渡す値を作る（カメラが動いた後）
+pub fn update_grid(
+    camera_query: Query<(&Transform, &OrthographicProjection), (With<Camera>, Without<GridQuad>)>,
+    player_query: Query<(&GridPosition, &Transform), (With<Player>, Without<Camera>, Without<GridQuad>)>,
+    window_query: Query<&Window>,
+    waypoints: Res<Waypoints>,
+    mut materials: ResMut<Assets<GridMaterial>>,
+    mut grid_query: Query<(&mut Transform, &MeshMaterial2d<GridMaterial>), With<GridQuad>>,
+) {
+    let Ok((cam_transform, projection)) = camera_query.get_single() else { return };
+    let Ok((player_pos, player_transform)) = player_query.get_single() else { return };
+    let Ok(window) = window_query.get_single() else { return };
+    let Ok((mut transform, material)) = grid_query.get_single_mut() else { return };
+
+    let view = Vec2::new(window.resolution.width(), window.resolution.height()) * projection.scale;
+    transform.translation = cam_transform.translation.truncate().extend(GRID_Z);
+    transform.scale = view.extend(1.0);
+
+    let mut step: i64 = 1;
+    while TILE_SIZE * step as f32 / projection.scale < GRID_MIN_SPACING {
+        step *= 2;
+    }
+
+    // カメラの位置 = プレイヤーのマス + ずれ（カメラがプレイヤーを追いかけている分と、プレイヤーが歩いている途中の分）
+    // マスの境目が整数になる座標 (マス x は x 〜 x + 1)
+    let player_pixel = player_transform.translation.truncate();
+    let offset = (cam_transform.translation.truncate() - player_pixel) / TILE_SIZE
+        + (player_pixel / TILE_SIZE - Vec2::new(player_pos.x as f32, player_pos.y as f32))
+        + Vec2::splat(0.5);
+    // origin はカメラのいるマスを step の倍数にそろえたもの
+    let cam_cell = (player_pos.x + offset.x.floor() as i64, player_pos.y + offset.y.floor() as i64);
+    let origin = (cam_cell.0.div_euclid(step) * step, cam_cell.1.div_euclid(step) * step);
+    let size = view / TILE_SIZE;
+
+    let mut params = GridParams {
+        color: GRID_COLOR.to_linear().to_vec4(),
+        center: Vec2::new((player_pos.x - origin.0) as f32, (player_pos.y - origin.1) as f32) + offset,
+        size,
+        step: step as f32,
+        ..default()
+    };
+
+    // 画面に映っているマスだけ塗る
+    let cells = std::iter::once(((0, 0), ORIGIN_HIGHLIGHT))
+        .chain(waypoints.points.iter().map(|w| ((w.x, w.y), WAYPOINT_HIGHLIGHT)));
+    for ((x, y), color) in cells {
+        let rel = Vec2::new(x.wrapping_sub(origin.0) as f32, y.wrapping_sub(origin.1) as f32);
+        if (rel - params.center).abs().cmpgt(size / 2.0 + 1.0).any() {
+            continue;
+        }
+        let i = params.highlight_count as usize;
+        if i == MAX_GRID_HIGHLIGHTS {
+            break;
+        }
+        params.highlights[i] = rel.extend(0.0).extend(0.0);
+        params.highlight_colors[i] = color.to_linear().to_vec4();
+        params.highlight_count += 1;
+    }
+
+    // 変わったときだけ書き換える（書き換えると GPU に送り直す）
+    if materials.get(&material.0).is_some_and(|m| m.params != params) {
+        if let Some(m) = materials.get_mut(&material.0) {
+            m.params = params;
+        }
+    }
+}
@@ -1,13 +1,16 @@
 use bevy::prelude::*;
 use crate::components::*;
 use crate::resources::*;
-use crate::constants::TILE_SIZE;
-use rand::seq::SliceRandom; // ランダムピック用
+use crate::constants::VOICE_RANGE;
+use crate::events::{EmoteEvent, SpeakEvent};
+use crate::storage::Storage;
+use crate::controls::{Action, ActionState, Controls};
 
 // 移動入力システム
 pub fn handle_movement_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     mut input_buffer: ResMut<InputBuffer>,
+    mut move_path: ResMut<MovePath>,
     emoji_state: Res<EmojiSelectState>,
     chat_menu_state: Res<ChatMenuState>,
 ) {
@@ -17,101 +20,127 @@ pub fn handle_movement_input(
     }
 
     let mut direction = Vec2::ZERO;
-    if keyboard_input.pressed(KeyCode::ArrowUp) { direction.y += 1.0; }
-    if keyboard_input.pressed(KeyCode::ArrowDown) { direction.y -= 1.0; }
-    if keyboard_input.pressed(KeyCode::ArrowLeft) { direction.x -= 1.0; }
-    if keyboard_input.pressed(KeyCode::ArrowRight) { direction.x += 1.0; }
+    if actions.pressed(Action::MoveUp) { direction.y += 1.0; }
+    if actions.pressed(Action::MoveDown) { direction.y -= 1.0; }
+    if actions.pressed(Action::MoveLeft) { direction.x -= 1.0; }
+    if actions.pressed(Action::MoveRight) { direction.x += 1.0; }
 
     if direction != Vec2::ZERO {
         input_buffer.0 = direction;
+        // 【新規】キーで動かしたらクリック移動はやめる
+        move_path.0.clear();
     }
 }
 
 // チャット・絵文字入力システム
+#[allow(clippy::collapsible_if)]
 pub fn handle_chat_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     mut chat_log: ResMut<ChatLog>,
     player_query: Query<&Vocabulary, With<Player>>,
-    mut emoji_query: Query<(&mut Text, &mut EmojiTimer), With<PlayerEmoji>>,
+    mut emoji_query: Query<(&mut Text2d, &mut EmojiTimer), With<PlayerEmoji>>,
     
     mut emoji_config: ResMut<EmojiConfig>,
     mut emoji_state: ResMut<EmojiSelectState>,
+    chat_menu_state: Res<ChatMenuState>,
 
     mut voice_query: Query<&mut Visibility, With<VoiceEffect>>,
     
-    // 【修正】BotDialogues リソースを追加
-    bot_dialogues: Res<BotDialogues>,
-    
-    mut bot_query: Query<(&Transform, &Children, &mut BotTalking), With<Bot>>,
-    mut bot_text_query: Query<(&mut Text2d, &mut BotChatTimer), With<BotChatText>>,
+    // 【変更】絵文字候補はコンテンツファイルから読み込んだものを使う
+    emoji_catalog: Res<EmojiCatalog>,
     
-    player_transform_query: Query<&Transform, With<Player>>,
+    // 【新規】絵文字・言葉を周囲のボットに届ける（反応は bot 側のシステムが担当）
+    player_grid_query: Query<&GridPosition, With<Player>>,
+    mut emote_events: EventWriter<EmoteEvent>,
+    mut speak_events: EventWriter<SpeakEvent>,
+
+    // 【新規】言葉の連投制限
+    time: Res<Time>,
+    mut chat_limit: ResMut<ChatRateLimit>,
+    mut notification: ResMut<NotificationState>,
 ) {
     if emoji_state.is_open {
-        let total_count = crate::resources::EMOJI_LIST.len();
-        if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        let choices = emoji_catalog.choices(&emoji_config.unlocked);
+        let total_count = choices.len();
+        if actions.just_pressed(Action::MoveUp) {
             if emoji_state.selected_index > 0 { emoji_state.selected_index -= 1; }
         }
-        if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        if actions.just_pressed(Action::MoveDown) {
             if emoji_state.selected_index < total_count.saturating_sub(1) { emoji_state.selected_index += 1; }
         }
-        if keyboard_input.just_pressed(KeyCode::Enter) {
-            let selected_emoji = crate::resources::EMOJI_LIST[emoji_state.selected_index];
-            match emoji_state.target_key {
-                Some(KeyCode::KeyS) => emoji_config.s_key = selected_emoji.to_string(),
-                Some(KeyCode::KeyD) => emoji_config.d_key = selected_emoji.to_string(),
-                _ => {}
+        if actions.just_pressed(Action::Confirm) {
+            // ホットリロードでリストが短くなっている可能性があるので get で取る
+            if let Some(selected_emoji) = choices.get(emoji_state.selected_index).map(|e| e.to_string()) {
+                match emoji_state.target_key {
+                    Some(Action::EmoteS) => emoji_config.s_key = selected_emoji,
+                    Some(Action::EmoteD) => emoji_config.d_key = selected_emoji,
+                    _ => {}
+                }
             }
             emoji_state.is_open = false;
             emoji_state.target_key = None;
         }
-        if keyboard_input.just_pressed(KeyCode::Escape) {
+        if actions.just_pressed(Action::Cancel) {
             emoji_state.is_open = false;
             emoji_state.target_key = None;
         }
         return; 
     }
 
-    if keyboard_input.just_pressed(KeyCode::KeyA) {
+    let player_cell = player_grid_query.get_single().ok().copied();
+    let now = time.elapsed_secs_f64();
+    // 【変更】絵文字も言葉と同じ連投制限にかける（サーバーも同じ制限で捨てる）
+    let mut emote = |emoji: &str| {
+        if !chat_limit.0.allow(now) {
+            notification.message = "Slow down!".to_string();
+            notification.is_visible = true;
+            notification.timer.reset();
+            return;
+        }
         if let Ok((mut text, mut timer)) = emoji_query.get_single_mut() {
-            text.0 = "👍".to_string();
+            text.0 = emoji.to_string();
             timer.0.reset();
         }
+        if let Some(cell) = player_cell {
+            emote_events.send(EmoteEvent { emoji: emoji.to_string(), x: cell.x, y: cell.y });
+        }
+    };
+
+    if actions.just_pressed(Action::EmoteThumb) {
+        emote(THUMB_EMOJI);
     }
-    if keyboard_input.just_pressed(KeyCode::KeyS) {
-        if keyboard_input.pressed(KeyCode::Space) {
+    if actions.just_pressed(Action::EmoteS) {
+        if actions.pressed(Action::OpenMenu) {
             emoji_state.is_open = true;
-            emoji_state.target_key = Some(KeyCode::KeyS);
+            emoji_state.target_key = Some(Action::EmoteS);
             emoji_state.selected_index = 0;
         } else {
-             if let Ok((mut text, mut timer)) = emoji_query.get_single_mut() {
-                text.0 = emoji_config.s_key.clone();
-                timer.0.reset();
-            }
+            emote(&emoji_config.s_key);
         }
     }
-    if keyboard_input.just_pressed(KeyCode::KeyD) {
-        if keyboard_input.pressed(KeyCode::Space) {
+    if actions.just_pressed(Action::EmoteD) {
+        if actions.pressed(Action::OpenMenu) {
             emoji_state.is_open = true;
-            emoji_state.target_key = Some(KeyCode::KeyD);
+            emoji_state.target_key = Some(Action::EmoteD);
             emoji_state.selected_index = 0;
         } else {
-             if let Ok((mut text, mut timer)) = emoji_query.get_single_mut() {
-                text.0 = emoji_config.d_key.clone();
-                timer.0.reset();
-            }
+            emote(&emoji_config.d_key);
         }
     }
 
     if let Ok(vocab) = player_query.get_single() {
-        let mut selected_index = None;
-        if keyboard_input.just_pressed(KeyCode::Digit1) { selected_index = Some(0); }
-        if keyboard_input.just_pressed(KeyCode::Digit2) { selected_index = Some(1); }
-        if keyboard_input.just_pressed(KeyCode::Digit3) { selected_index = Some(2); }
-        if keyboard_input.just_pressed(KeyCode::Digit4) { selected_index = Some(3); }
+        // 【変更】チャットメニューを開いているときは、選んでいる言葉を Confirm でも言える（ゲームパッド向け）
+        let selected_index = (0..4u8)
+            .find(|n| actions.just_pressed(Action::Speak(*n)))
+            .map(usize::from)
+            .or_else(|| (chat_menu_state.is_open && actions.just_pressed(Action::Confirm)).then_some(chat_menu_state.selected_index));
 
         if let Some(index) = selected_index {
-            if index < vocab.words.len() {
+            if index < vocab.words.len() && !chat_limit.0.allow(now) {
+                notification.message = "Slow down!".to_string();
+                notification.is_visible = true;
+                notification.timer.reset();
+            } else if index < vocab.words.len() {
                 let word = &vocab.words[index];
                 
                 chat_log.messages.push((
@@ -123,38 +152,59 @@ pub fn handle_chat_input(
                     *visibility = Visibility::Visible;
                 }
 
-                if let Ok(p_transform) = player_transform_query.get_single() {
-                    let range = TILE_SIZE * 4.5; 
-
-                    for (b_transform, children, mut talking) in &mut bot_query {
-                        let dist = p_transform.translation.distance(b_transform.translation);
-                        
-                        if dist <= range {
-                            // 【修正】ランダムピック処理
-                            let response = if word == "Hello" {
-                                "Hello".to_string()
-                            } else {
-                                // リソースからランダムに1行選ぶ
-                                if let Some(line) = bot_dialogues.lines.choose(&mut rand::thread_rng()) {
-                                    line.clone()
-                                } else {
-                                    "...".to_string() // ファイルが空の場合
-                                }
-                            };
-
-                            talking.0.set_duration(std::time::Duration::from_secs(3));
-                            talking.0.reset();
-
-                            for &child in children.iter() {
-                                if let Ok((mut text, mut timer)) = bot_text_query.get_mut(child) {
-                                    text.0 = response.clone(); 
-                                    timer.0.reset();
-                                }
-                            }
-                        }
-                    }
+                if let Some(cell) = player_cell {
+                    speak_events.send(SpeakEvent { word: word.clone(), x: cell.x, y: cell.y });
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+// 【新規】ミュート (M キー)
+// 声の届く範囲で一番近い他のプレイヤーをミュート / 解除する。設定はユーザーごとに保存される
+pub fn handle_mute_input(
+    actions: Res<ActionState>,
+    emoji_state: Res<EmojiSelectState>,
+    storage: Res<Storage>,
+    mut current_user: ResMut<CurrentUser>,
+    mut notification: ResMut<NotificationState>,
+    controls: Res<Controls>,
+    player_query: Query<&GridPosition, With<Player>>,
+    other_query: Query<(&OtherPlayer, &GridPosition)>,
+) {
+    if emoji_state.is_open || !actions.just_pressed(Action::Mute) {
+        return;
+    }
+    let Ok(pos) = player_query.get_single() else { return };
+
+    let nearest = other_query
+        .iter()
+        .map(|(other, other_pos)| (other, (other_pos.x - pos.x).abs().max((other_pos.y - pos.y).abs())))
+        .filter(|(_, distance)| *distance <= VOICE_RANGE)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(other, _)| other.name.clone());
+    let Some(name) = nearest else {
+        // 【変更】一覧の開き方も知らせる
+        notification.message = format!(
+            "No one nearby to mute ([{}]+[{}] lists muted players)",
+            controls.key_label(Action::OpenMenu),
+            controls.key_label(Action::Mute),
+        );
+        notification.is_visible = true;
+        notification.timer.reset();
+        return;
+    };
+
+    let muted = !current_user.muted.contains(&name);
+    if let Err(e) = storage.set_muted(&current_user.username, &name, muted) {
+        eprintln!("Mute Save Error: {}", e);
+    }
+    if muted {
+        current_user.muted.push(name.clone());
+        notification.message = format!("Muted {}", name);
+    } else {
+        current_user.muted.retain(|m| *m != name);
+        notification.message = format!("Unmuted {}", name);
+    }
+    notification.is_visible = true;
+    notification.timer.reset();
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use crate::constants::TILE_SIZE;
+use crate::components::*;
+use crate::resources::*;
+use crate::map::{is_item_spawn, item_index};
+use crate::storage::Storage;
+use std::collections::HashSet;
+
+// ==========================================
+// 【新規】フィールドのアイテム
+// 出現場所は map::is_item_spawn で決まる。表示はボットと同じく画面付近だけスポーンする
+// ==========================================
+pub fn spawn_visible_items(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    camera_query: Query<(&GlobalTransform, &OrthographicProjection), With<Camera>>,
+    window_query: Query<&Window>,
+    existing_items: Query<&ItemMarker>,
+    item_table: Res<ItemTable>,
+    taken: Res<TakenItems>,
+) {
+    let Ok((cam_transform, projection)) = camera_query.get_single() else { return };
+    let Ok(window) = window_query.get_single() else { return };
+    if item_table.items.is_empty() { return; }
+
+    let emoji_font = asset_server.load("fonts/NotoEmoji-Bold.ttf");
+
+    let view_half_width = window.resolution.width() / 2.0 * projection.scale;
+    let view_half_height = window.resolution.height() / 2.0 * projection.scale;
+    let cam_pos = cam_transform.translation();
+
+    let buffer = 2.0;
+    let start_x = ((cam_pos.x - view_half_width) / TILE_SIZE - buffer).floor() as i64;
+    let end_x = ((cam_pos.x + view_half_width) / TILE_SIZE + buffer).ceil() as i64;
+    let start_y = ((cam_pos.y - view_half_height) / TILE_SIZE - buffer).floor() as i64;
+    let end_y = ((cam_pos.y + view_half_height) / TILE_SIZE + buffer).ceil() as i64;
+
+    let existing_positions: HashSet<(i64, i64)> = existing_items.iter()
+        .map(|m| (m.x, m.y))
+        .collect();
+
+    for x in start_x..=end_x {
+        for y in start_y..=end_y {
+            if is_item_spawn(x, y) && !taken.0.contains(&(x, y)) && !existing_positions.contains(&(x, y)) {
+                let item = &item_table.items[item_index(x, y, item_table.items.len())];
+                commands.spawn((
+                    ItemMarker { x, y },
+                    GameEntity,
+                    Text2d::new(item.emoji.clone()),
+                    TextFont { font: emoji_font.clone(), font_size: 24.0, ..default() },
+                    TextColor(Color::srgb(0.8, 0.6, 0.0)),
+                    Transform::from_xyz(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE, 0.3),
+                ));
+            }
+        }
+    }
+}
+
+pub fn despawn_far_items(
+    mut commands: Commands,
+    camera_query: Query<(&GlobalTransform, &OrthographicProjection), With<Camera>>,
+    window_query: Query<&Window>,
+    item_query: Query<(Entity, &Transform), With<ItemMarker>>,
+) {
+    let Ok((cam_transform, projection)) = camera_query.get_single() else { return };
+    let Ok(window) = window_query.get_single() else { return };
+
+    let cam_pos = cam_transform.translation();
+    let limit_dist = window.resolution.width().max(window.resolution.height()) * projection.scale * 2.0;
+
+    for (entity, transform) in &item_query {
+        if transform.translation.distance(cam_pos) > limit_dist {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// 【新規】ログイン時に読んだ拾ったマスを TakenItems に移す
+pub fn setup_taken_items(mut current_user: ResMut<CurrentUser>, mut taken: ResMut<TakenItems>) {
+    taken.0 = std::mem::take(&mut current_user.taken_items).into_iter().collect();
+}
+
+// アイテムのマスに乗ったら拾う
+// 【変更】拾ったマスは DB にも記録し、ログインし直しても空のまま (TakenItems)
+pub fn pickup_items(
+    mut commands: Commands,
+    mut player_query: Query<(&GridPosition, &mut Inventory), (With<Player>, Changed<GridPosition>)>,
+    item_query: Query<(Entity, &ItemMarker)>,
+    item_table: Res<ItemTable>,
+    storage: Res<Storage>,
+    current_user: Res<CurrentUser>,
+    mut taken: ResMut<TakenItems>,
+    mut notification: ResMut<NotificationState>,
+) {
+    let Ok((pos, mut inventory)) = player_query.get_single_mut() else { return };
+    if item_table.items.is_empty() { return; }
+    if !is_item_spawn(pos.x, pos.y) || taken.0.contains(&(pos.x, pos.y)) { return; }
+
+    let item = &item_table.items[item_index(pos.x, pos.y, item_table.items.len())];
+    if let Err(e) = storage.take_item(&current_user.username, pos.x, pos.y, &item.id) {
+        eprintln!("Item Save Error: {}", e);
+    }
+    inventory.items.push(item.id.clone());
+    taken.0.insert((pos.x, pos.y));
+
+    for (entity, marker) in &item_query {
+        if marker.x == pos.x && marker.y == pos.y {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    notification.message = format!("Got {}!", item.name);
+    notification.is_visible = true;
+    notification.timer.reset();
+}
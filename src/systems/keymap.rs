@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::storage::Storage;
+use crate::controls::{is_bindable_key, key_name, stick_actions, Action, ActionState, Controls};
+
+// ==========================================
+// 【新規】操作の割り当て
+// 毎フレーム、キーボード・ゲームパッド・タッチの入力を ActionState にまとめる。
+// K キーで割り当て画面を開き、選んだ操作に次に押したキー / ボタンを割り当てる。
+// 閉じたときに変わっていれば保存する
+// ==========================================
+
+#[derive(Resource, Default)]
+pub struct ControlsMenu {
+    pub is_open: bool,
+    // Action::ALL の添字
+    selected: usize,
+    // 次に押したキー / ボタンを割り当てる
+    listening: bool,
+    message: String,
+    // 開いたときの割り当て（閉じるときに比べて、変わっていれば保存）
+    opened_with: String,
+}
+
+#[derive(Component)]
+pub struct ControlsMenuDisplay;
+
+// 割り当てに従ってこのフレームの操作を決める（PreUpdate の UI の判定とタッチの後）
+pub fn update_action_state(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    controls: Res<Controls>,
+    mut actions: ResMut<ActionState>,
+) {
+    let mut pressed = std::mem::take(&mut actions.injected);
+    for action in Action::ALL {
+        if controls.keys(action).iter().any(|k| keyboard_input.pressed(*k))
+            || controls.buttons(action).iter().any(|b| gamepads.iter().any(|g| g.pressed(*b)))
+        {
+            pressed.insert(action);
+        }
+    }
+    // 【新規】左スティックは割り当てを変えられない移動（メニューの上下にも使える）
+    for gamepad in &gamepads {
+        pressed.extend(stick_actions(gamepad.left_stick()));
+    }
+    actions.update(pressed);
+}
+
+// ログイン時に保存した割り当てを読み込み、割り当て画面を作る
+pub fn setup_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    current_user: Res<CurrentUser>,
+    mut controls: ResMut<Controls>,
+    mut menu: ResMut<ControlsMenu>,
+) {
+    *controls = Controls::from_saved(&current_user.key_bindings);
+    *menu = ControlsMenu::default();
+
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    commands.spawn((
+        Text::new(""),
+        TextFont { font: jp_font, font_size: 16.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(40.0),
+            display: Display::None,
+            padding: UiRect::all(Val::Px(15.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        ControlsMenuDisplay,
+        BackgroundColor(Color::srgba(0.0, 0.2, 0.2, 0.95)),
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(10.0)),
+        ZIndex(120),
+        GameEntity,
+    ));
+}
+
+// 割り当て画面の操作。開いている間は他のシステムに入力を渡さない
+pub fn handle_controls_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut actions: ResMut<ActionState>,
+    mut controls: ResMut<Controls>,
+    mut menu: ResMut<ControlsMenu>,
+    emoji_state: Res<EmojiSelectState>,
+    storage: Res<Storage>,
+    mut current_user: ResMut<CurrentUser>,
+) {
+    if !menu.is_open {
+        if actions.just_pressed(Action::Controls) && !emoji_state.is_open {
+            *menu = ControlsMenu { is_open: true, opened_with: controls.to_saved(), ..default() };
+            actions.consume();
+        }
+        return;
+    }
+    let action = Action::ALL[menu.selected];
+
+    if menu.listening {
+        // 割り当てる入力はキーそのものを読む（Escape はやめる）
+        if let Some(key) = keyboard_input.get_just_pressed().next().copied() {
+            menu.listening = false;
+            menu.message = if key == KeyCode::Escape {
+                String::new()
+            } else if !is_bindable_key(key) {
+                format!("{} can't be assigned", key_name(key))
+            } else {
+                match controls.bind_key(action, key) {
+                    Ok(()) => format!("{} -> {}", action.label(), key_name(key)),
+                    Err(e) => e,
+                }
+            };
+        } else if let Some(button) = gamepads.iter().find_map(|g| g.get_just_pressed().next().copied()) {
+            menu.listening = false;
+            controls.bind_button(action, button);
+            menu.message = format!("{} -> {:?}", action.label(), button);
+        }
+    } else if actions.just_pressed(Action::Cancel) || actions.just_pressed(Action::Controls) {
+        let saved = controls.to_saved();
+        if saved != menu.opened_with {
+            if let Err(e) = storage.save_bindings(&current_user.username, &saved) {
+                eprintln!("Bindings Save Error: {}", e);
+            }
+            current_user.key_bindings = saved;
+        }
+        menu.is_open = false;
+    } else if actions.just_pressed(Action::MoveUp) {
+        menu.selected = menu.selected.checked_sub(1).unwrap_or(Action::ALL.len() - 1);
+    } else if actions.just_pressed(Action::MoveDown) {
+        menu.selected = (menu.selected + 1) % Action::ALL.len();
+    } else if actions.just_pressed(Action::Confirm) {
+        menu.listening = true;
+        menu.message = "Press a key or button... (Esc to cancel)".to_string();
+    } else if actions.just_pressed(Action::Remove) {
+        controls.reset(action);
+        menu.message = format!("{} reset to default", action.label());
+    }
+    actions.consume();
+}
+
+pub fn update_controls_menu_ui(
+    menu: Res<ControlsMenu>,
+    controls: Res<Controls>,
+    mut query: Query<(&mut Text, &mut Node), With<ControlsMenuDisplay>>,
+) {
+    let Ok((mut text, mut node)) = query.get_single_mut() else { return };
+    if !menu.is_open {
+        node.display = Display::None;
+        return;
+    }
+    node.display = Display::Flex;
+    if !menu.is_changed() && !controls.is_changed() {
+        return;
+    }
+
+    let mut content = "Controls  (keys / gamepad buttons, left stick always moves)\n\n".to_string();
+    for (i, action) in Action::ALL.iter().enumerate() {
+        let marker = if i == menu.selected { ">" } else { " " };
+        let keys: Vec<String> = controls.keys(*action).iter().map(|k| key_name(*k)).collect();
+        let buttons: Vec<String> = controls.buttons(*action).iter().map(|b| format!("{:?}", b)).collect();
+        let fixed = if *action == Action::EmoteThumb { " (fixed)" } else { "" };
+        content.push_str(&format!(
+            "{} {:<18} {}{}  / {}\n",
+            marker,
+            action.label(),
+            if keys.is_empty() { "-".to_string() } else { keys.join(", ") },
+            fixed,
+            if buttons.is_empty() { "-".to_string() } else { buttons.join(", ") },
+        ));
+    }
+    if !menu.message.is_empty() {
+        content.push_str(&format!("\n{}\n", menu.message));
+    }
+    content.push_str(&format!(
+        "\n[{}/{}] Select  [{}] Rebind  [{}] Reset  [{}] Close",
+        controls.key_label(Action::MoveUp),
+        controls.key_label(Action::MoveDown),
+        controls.key_label(Action::Confirm),
+        controls.key_label(Action::Remove),
+        controls.key_label(Action::Cancel),
+    ));
+    text.0 = content;
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use crate::components::GameEntity;
+use crate::resources::*;
+use crate::storage::Storage;
+use crate::net::ClientMessage;
+use crate::constants::LEADERBOARD_SIZE;
+use crate::controls::{Action, ActionState, Controls};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::database;
+
+// ==========================================
+// 【新規】探索マス数のランキング
+// 開くたびに読み直す。サーバー接続中は問い合わせだけ送り、返事は net_client が rows に入れる
+// ==========================================
+
+#[derive(Resource, Default)]
+pub struct Leaderboard {
+    pub is_open: bool,
+    // (username, explored_cells, points)。None は読み込み中
+    pub rows: Option<Vec<(String, i64, i32)>>,
+}
+
+#[derive(Component)]
+pub struct LeaderboardDisplay;
+
+pub fn setup_leaderboard(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    *leaderboard = Leaderboard::default();
+
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    commands.spawn((
+        Text::new(""),
+        TextFont { font: jp_font, font_size: 16.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            left: Val::Px(20.0),
+            display: Display::None,
+            padding: UiRect::all(Val::Px(12.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        LeaderboardDisplay,
+        BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.9)),
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(10.0)),
+        ZIndex(110),
+        GameEntity,
+    ));
+}
+
+// 開いている間はほかの入力を止める（PreUpdate、ミュートの一覧の後）
+pub fn handle_leaderboard_input(
+    mut actions: ResMut<ActionState>,
+    mut leaderboard: ResMut<Leaderboard>,
+    storage: Res<Storage>,
+) {
+    if !leaderboard.is_open {
+        if actions.just_pressed(Action::Leaderboard) {
+            leaderboard.is_open = true;
+            leaderboard.rows = match &*storage {
+                #[cfg(not(target_arch = "wasm32"))]
+                Storage::Local(db_pool) => match database::load_leaderboard(&db_pool.0, LEADERBOARD_SIZE) {
+                    Ok(rows) => Some(rows),
+                    Err(e) => {
+                        eprintln!("Leaderboard Load Error: {}", e);
+                        Some(Vec::new())
+                    }
+                },
+                Storage::Remote(sender) => {
+                    let _ = sender.send(ClientMessage::LoadLeaderboard);
+                    None
+                }
+            };
+            actions.consume();
+        }
+        return;
+    }
+
+    if actions.just_pressed(Action::Cancel) || actions.just_pressed(Action::Leaderboard) {
+        leaderboard.is_open = false;
+    }
+    actions.consume();
+}
+
+pub fn update_leaderboard_ui(
+    leaderboard: Res<Leaderboard>,
+    current_user: Res<CurrentUser>,
+    explored: Res<Explored>,
+    controls: Res<Controls>,
+    mut query: Query<(&mut Text, &mut Node), With<LeaderboardDisplay>>,
+) {
+    let Ok((mut text, mut node)) = query.get_single_mut() else { return };
+    if !leaderboard.is_open {
+        node.display = Display::None;
+        return;
+    }
+    node.display = Display::Flex;
+
+    let mut content = "Explorers\n".to_string();
+    match &leaderboard.rows {
+        None => content.push_str("  Loading...\n"),
+        Some(rows) if rows.is_empty() => content.push_str("  (no one yet)\n"),
+        Some(rows) => {
+            for (i, (name, cells, points)) in rows.iter().enumerate() {
+                let marker = if *name == current_user.username { "▶" } else { " " };
+                content.push_str(&format!("{} {:>2}. {}  {} cells  {} pts\n", marker, i + 1, name, cells, points));
+            }
+        }
+    }
+    content.push_str(&format!("\nYou: {} cells", explored.0.cells));
+    content.push_str(&format!("\n\n[{}] Close", controls.key_label(Action::Cancel)));
+    if text.0 != content {
+        text.0 = content;
+    }
+}
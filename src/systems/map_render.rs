@@ -1,16 +1,22 @@
 use bevy::prelude::*;
 use crate::constants::TILE_SIZE;
-use crate::map::is_obstacle;
+use crate::map::{is_obstacle, is_teleport_stone};
 // 未使用のインポートを削除しました
 
 #[derive(Component)]
 pub struct Obstacle;
 
+// 【新規】テレポート石（障害物と同じく毎フレーム出し直す）
+#[derive(Component)]
+pub struct TeleportStone;
+
+const TELEPORT_STONE_COLOR: Color = Color::srgb(0.0, 0.67, 0.67);
+
 pub fn spawn_visible_obstacles(
     mut commands: Commands,
     camera_query: Query<(&Camera, &GlobalTransform, &OrthographicProjection)>,
     window_query: Query<&Window>,
-    obstacle_query: Query<Entity, With<Obstacle>>,
+    obstacle_query: Query<Entity, Or<(With<Obstacle>, With<TeleportStone>)>>,
 ) {
     // camera 変数は使わないので _ に変更
     let (_, cam_transform, projection) = camera_query.single();
@@ -42,6 +48,17 @@ pub fn spawn_visible_obstacles(
                     },
                     Transform::from_xyz(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE, 0.0),
                 ));
+            } else if is_teleport_stone(x, y) {
+                commands.spawn((
+                    TeleportStone,
+                    Sprite {
+                        color: TELEPORT_STONE_COLOR,
+                        custom_size: Some(Vec2::new(TILE_SIZE * 0.6, TILE_SIZE * 0.6)),
+                        ..default()
+                    },
+                    Transform::from_xyz(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE, 0.0)
+                        .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+                ));
             }
         }
     }
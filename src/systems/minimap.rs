@@ -0,0 +1,327 @@
+use bevy::prelude::*;
+use bevy::image::ImageSampler;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use crate::components::*;
+use crate::resources::*;
+use crate::map::{is_teleport_stone, tile_at, Tile};
+use crate::explore::ExploredMap;
+use crate::controls::{Action, ActionState, Controls};
+
+// ==========================================
+// 【新規】ミニマップ（右下）とワールドマップ（N キーで全画面）
+// エンティティは使わず、map.rs の生成関数から直接テクスチャに描く。
+// プレイヤーが別のマスに移ったときと、表示が切り替わったときだけ描き直す
+// 【変更】まだ探索していない場所は暗く描く
+// 【変更】ワールドマップを開いている間は他の操作を止める（ウェイポイントの一覧を選ぶため）
+// 【変更】拾ったアイテム (TakenItems) は描かない
+// ==========================================
+
+// ミニマップ: 周囲 MINIMAP_CELLS マス四方を 1 マス 1 ピクセルで描き、画面では 3 倍にする
+const MINIMAP_CELLS: u32 = 45;
+const MINIMAP_DISPLAY_SCALE: f32 = 3.0;
+
+// ワールドマップのテクスチャの大きさ（ウィンドウの 4:3 に合わせる）
+const WORLD_MAP_WIDTH: u32 = 200;
+const WORLD_MAP_HEIGHT: u32 = 150;
+
+// ワールドマップの縮尺（1 ピクセルが何マスおきか）
+pub const WORLD_MAP_ZOOMS: [i64; 6] = [1, 4, 16, 64, 256, 1024];
+
+const PLAYER_MARK: [u8; 3] = [0, 0, 0];
+const ORIGIN_MARK: [u8; 3] = [220, 0, 0];
+const WAYPOINT_MARK: [u8; 3] = [200, 0, 200];
+const SELECTED_WAYPOINT_MARK: [u8; 3] = [255, 140, 0];
+
+// 描画先の画像と、最後に描いたときの中心（同じなら描き直さない）
+#[derive(Resource, Default)]
+pub struct MapState {
+    pub minimap: Handle<Image>,
+    pub world_map: Handle<Image>,
+    pub drawn_center: Option<(i64, i64)>,
+    pub world_drawn: Option<((i64, i64), i64, Option<usize>)>,
+    pub world_open: bool,
+    // WORLD_MAP_ZOOMS の添字
+    pub zoom: usize,
+    // 【新規】ワールドマップで選んでいるウェイポイント（Waypoints の添字）
+    pub selected_waypoint: Option<usize>,
+}
+
+#[derive(Component)]
+pub struct MinimapDisplay;
+
+#[derive(Component)]
+pub struct WorldMapDisplay;
+
+#[derive(Component)]
+pub struct WorldMapText;
+
+fn new_map_image(width: u32, height: u32) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d { width, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[255, 255, 255, 255],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    // 拡大してもぼやけないように
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+pub fn setup_minimap(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut map_state: ResMut<MapState>,
+) {
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    map_state.minimap = images.add(new_map_image(MINIMAP_CELLS, MINIMAP_CELLS));
+    map_state.world_map = images.add(new_map_image(WORLD_MAP_WIDTH, WORLD_MAP_HEIGHT));
+    map_state.drawn_center = None;
+    map_state.world_drawn = None;
+
+    let minimap_size = MINIMAP_CELLS as f32 * MINIMAP_DISPLAY_SCALE;
+    commands.spawn((
+        ImageNode::new(map_state.minimap.clone()),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(80.0),
+            right: Val::Px(20.0),
+            width: Val::Px(minimap_size),
+            height: Val::Px(minimap_size),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor(Color::BLACK),
+        // クリック移動がミニマップの上の押下を無視できるように
+        Interaction::default(),
+        MinimapDisplay,
+        GameEntity,
+    ));
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            display: Display::None,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        WorldMapDisplay,
+        ZIndex(100),
+        GameEntity,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            ImageNode::new(map_state.world_map.clone()),
+            Node {
+                width: Val::Percent(85.0),
+                height: Val::Percent(85.0),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+        ));
+        parent.spawn((
+            Text::new(""),
+            TextFont { font: jp_font, font_size: 18.0, ..default() },
+            TextColor(Color::WHITE),
+            Node { margin: UiRect::top(Val::Px(8.0)), ..default() },
+            WorldMapText,
+        ));
+    });
+}
+
+// N でワールドマップを開閉、開いている間は +/- で縮尺を変える
+// 【変更】PreUpdate で動かし、開いている間の入力は他のシステムに渡さない
+pub fn handle_world_map_input(
+    mut actions: ResMut<ActionState>,
+    mut map_state: ResMut<MapState>,
+    mut display_query: Query<&mut Node, With<WorldMapDisplay>>,
+) {
+    let was_open = map_state.world_open;
+    if actions.just_pressed(Action::WorldMap) {
+        map_state.world_open = !map_state.world_open;
+    } else if map_state.world_open && actions.just_pressed(Action::Cancel) {
+        map_state.world_open = false;
+    }
+    if map_state.world_open {
+        if actions.just_pressed(Action::ZoomIn) {
+            map_state.zoom = map_state.zoom.saturating_sub(1);
+        }
+        if actions.just_pressed(Action::ZoomOut) {
+            map_state.zoom = (map_state.zoom + 1).min(WORLD_MAP_ZOOMS.len() - 1);
+        }
+    }
+
+    if let Ok(mut node) = display_query.get_single_mut() {
+        node.display = if map_state.world_open { Display::Flex } else { Display::None };
+    }
+    if was_open || map_state.world_open {
+        actions.consume();
+    }
+}
+
+pub fn update_minimap(
+    mut map_state: ResMut<MapState>,
+    mut images: ResMut<Assets<Image>>,
+    waypoints: Res<Waypoints>,
+    explored: Res<Explored>,
+    taken: Res<TakenItems>,
+    player_query: Query<&GridPosition, With<Player>>,
+) {
+    let Ok(pos) = player_query.get_single() else { return };
+    let center = (pos.x, pos.y);
+    if map_state.drawn_center == Some(center) && !waypoints.is_changed() && !taken.is_changed() {
+        return;
+    }
+    let Some(image) = images.get_mut(&map_state.minimap) else { return };
+    draw_map(image, center, 1, &waypoints, None, &explored.0, &taken);
+    map_state.drawn_center = Some(center);
+}
+
+pub fn update_world_map(
+    mut map_state: ResMut<MapState>,
+    mut images: ResMut<Assets<Image>>,
+    waypoints: Res<Waypoints>,
+    explored: Res<Explored>,
+    taken: Res<TakenItems>,
+    controls: Res<Controls>,
+    player_query: Query<&GridPosition, With<Player>>,
+    mut text_query: Query<&mut Text, With<WorldMapText>>,
+) {
+    if !map_state.world_open {
+        return;
+    }
+    let Ok(pos) = player_query.get_single() else { return };
+    let step = WORLD_MAP_ZOOMS[map_state.zoom];
+    let key = ((pos.x, pos.y), step, map_state.selected_waypoint);
+    if map_state.world_drawn == Some(key) && !waypoints.is_changed() && !taken.is_changed() {
+        return;
+    }
+    let Some(image) = images.get_mut(&map_state.world_map) else { return };
+    draw_map(image, (pos.x, pos.y), step, &waypoints, map_state.selected_waypoint, &explored.0, &taken);
+    map_state.world_drawn = Some(key);
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.0 = format!(
+            "({}, {})   1 px = {} cells   Explored: {} cells   [{}/{}] Zoom   [{}] Close\ngray: obstacle   blue: bot   gold: item   teal: teleport stone   red: origin   purple: waypoint   dark: unexplored",
+            pos.x, pos.y, step, explored.0.cells,
+            controls.key_label(Action::ZoomIn), controls.key_label(Action::ZoomOut), controls.key_label(Action::WorldMap)
+        );
+    }
+}
+
+// center を中心に、1 ピクセル step マスおきにテクスチャ全体を描く（上が +y）
+// 1 ピクセルは step * step マスの範囲を表し、その中に探索済みのマスが 1 つでもあれば明るく描く
+// 【変更】selected のウェイポイントは色を変えて大きく描く
+fn draw_map(image: &mut Image, center: (i64, i64), step: i64, waypoints: &Waypoints, selected: Option<usize>, explored: &ExploredMap, taken: &TakenItems) {
+    let width = image.width() as i64;
+    let height = image.height() as i64;
+    let (half_w, half_h) = (width / 2, height / 2);
+
+    // ワールド座標 → ピクセル（範囲外なら None）
+    let to_pixel = |x: i64, y: i64| -> Option<(i64, i64)> {
+        let px = (x - center.0).div_euclid(step) + half_w;
+        let py = half_h - (y - center.1).div_euclid(step);
+        (0..width).contains(&px).then_some(())?;
+        (0..height).contains(&py).then_some((px, py))
+    };
+
+    let mut lit = vec![false; (width * height) as usize];
+    explored.for_each_in(
+        center.0 - half_w * step,
+        center.1 + (half_h - height + 1) * step,
+        center.0 + (width - half_w) * step - 1,
+        center.1 + (half_h + 1) * step - 1,
+        |x, y| {
+            if let Some((px, py)) = to_pixel(x, y) {
+                lit[(py * width + px) as usize] = true;
+            }
+        },
+    );
+
+    let mut painter = Painter { data: &mut image.data, width, height };
+    for py in 0..height {
+        let y = center.1 + (half_h - py) * step;
+        for px in 0..width {
+            let x = center.0 + (px - half_w) * step;
+            let rgb = visible_tile(x, y, taken).rgb();
+            let rgb = if lit[(py * width + px) as usize] { rgb } else { rgb.map(|c| c / 3 + 30) };
+            painter.set(px, py, rgb);
+        }
+    }
+
+    for (i, waypoint) in waypoints.points.iter().enumerate() {
+        if let Some((px, py)) = to_pixel(waypoint.x, waypoint.y) {
+            if selected == Some(i) {
+                painter.block(px, py, SELECTED_WAYPOINT_MARK);
+            } else {
+                painter.cross(px, py, WAYPOINT_MARK);
+            }
+        }
+    }
+
+    // 原点: 範囲内なら印、範囲外なら端に原点の方向を示す
+    match to_pixel(0, 0) {
+        Some((px, py)) => painter.cross(px, py, ORIGIN_MARK),
+        None => {
+            let dx = (-center.0) as f64;
+            let dy = center.1 as f64;
+            let scale = ((half_w - 1) as f64 / dx.abs()).min((half_h - 1) as f64 / dy.abs());
+            let px = half_w + (dx * scale) as i64;
+            let py = half_h + (dy * scale) as i64;
+            painter.block(px, py, ORIGIN_MARK);
+        }
+    }
+
+    painter.block(half_w, half_h, PLAYER_MARK);
+}
+
+// 【新規】拾ったアイテムのマスは、アイテムの下にあるもの（テレポート石か何もない）として描く
+fn visible_tile(x: i64, y: i64, taken: &TakenItems) -> Tile {
+    match tile_at(x, y) {
+        Tile::Item if taken.0.contains(&(x, y)) => {
+            if is_teleport_stone(x, y) { Tile::TeleportStone } else { Tile::Empty }
+        }
+        tile => tile,
+    }
+}
+
+struct Painter<'a> {
+    data: &'a mut Vec<u8>,
+    width: i64,
+    height: i64,
+}
+
+impl Painter<'_> {
+    fn set(&mut self, x: i64, y: i64, rgb: [u8; 3]) {
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            let i = ((y * self.width + x) * 4) as usize;
+            self.data[i..i + 3].copy_from_slice(&rgb);
+            self.data[i + 3] = 255;
+        }
+    }
+
+    // 3x3 の四角
+    fn block(&mut self, x: i64, y: i64, rgb: [u8; 3]) {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                self.set(x + dx, y + dy, rgb);
+            }
+        }
+    }
+
+    // 十字
+    fn cross(&mut self, x: i64, y: i64, rgb: [u8; 3]) {
+        for d in -1..=1 {
+            self.set(x + d, y, rgb);
+            self.set(x, y + d, rgb);
+        }
+    }
+}
@@ -1,8 +1,57 @@
+// 描画・ウィンドウ・UI を使うもの (クライアントのみ)
+#[cfg(feature = "client")]
 pub mod startup;
+#[cfg(feature = "client")]
 pub mod input;
+#[cfg(feature = "client")]
 pub mod movement;
+#[cfg(feature = "client")]
 pub mod camera;
+#[cfg(feature = "client")]
 pub mod ui;
+#[cfg(feature = "client")]
 pub mod map_render;
+#[cfg(feature = "client")]
 pub mod account;
-pub mod bot; // 新規追加
\ No newline at end of file
+#[cfg(feature = "client")]
+pub mod bot; // 新規追加
+#[cfg(feature = "client")]
+pub mod content;
+#[cfg(feature = "client")]
+pub mod items;
+#[cfg(feature = "client")]
+pub mod quest;
+#[cfg(feature = "client")]
+pub mod net_client;
+#[cfg(feature = "client")]
+pub mod bot_memory;
+#[cfg(feature = "client")]
+pub mod minimap;
+#[cfg(feature = "client")]
+pub mod explore;
+#[cfg(feature = "client")]
+pub mod click_move;
+#[cfg(feature = "client")]
+pub mod touch;
+#[cfg(feature = "client")]
+pub mod keymap;
+#[cfg(feature = "client")]
+pub mod waypoint;
+#[cfg(feature = "client")]
+pub mod avatar;
+#[cfg(feature = "client")]
+pub mod grid;
+#[cfg(feature = "client")]
+pub mod mute;
+#[cfg(feature = "client")]
+pub mod leaderboard;
+// DB に直接つなぐので、ブラウザ版にはない
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub mod presence;
+
+// クライアントとサーバーで共有するもの
+pub mod bot_wander;
+pub mod spatial;
+// サーバーはブラウザでは動かない
+#[cfg(not(target_arch = "wasm32"))]
+pub mod net_server;
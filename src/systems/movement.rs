@@ -1,22 +1,56 @@
 use bevy::prelude::*;
 use crate::components::*;
 use crate::resources::*;
-use crate::constants::TILE_SIZE;
+use crate::constants::{PLAYER_MOVE_INTERVAL, TILE_SIZE};
 use crate::map::is_obstacle;
+use crate::controls::{Action, ActionState};
+use crate::stamina::StaminaConfig;
+use std::time::Duration;
 
 // ==========================================
 // 【CORE LOGIC】 グリッド移動システム
 // 役割: 1秒に1回、データ上の位置(GridPosition)だけを更新する。
 // 注意: ここで Transform (見た目) は絶対に触らないこと！
 // ==========================================
+#[allow(clippy::collapsible_if)]
 pub fn move_player_tick(
     mut move_timer: ResMut<MoveTimer>,
     time: Res<Time>,
     mut input_buffer: ResMut<InputBuffer>,
+    // 【新規】クリック移動の経路（キー入力がないときに 1 歩ずつ使う）
+    mut move_path: ResMut<MovePath>,
+    // 【新規】ダッシュ（押している間、スタミナがあれば間隔を短くする）
+    actions: Res<ActionState>,
+    stamina_config: Res<StaminaConfig>,
+    mut stamina_query: Query<&mut Stamina, With<Player>>,
     mut query: Query<&mut GridPosition, With<Player>>,
 ) {
+    let Ok(mut stamina) = stamina_query.get_single_mut() else { return };
+    let wants_to_move = input_buffer.0 != Vec2::ZERO || !move_path.0.is_empty();
+    let sprinting = actions.pressed(Action::Sprint) && wants_to_move && stamina.0 >= stamina_config.step_cost;
+    if !sprinting {
+        stamina.0 = (stamina.0 + stamina_config.regen_per_sec * time.delta_secs()).min(stamina_config.max);
+    }
+    let interval = Duration::from_secs_f32(if sprinting { stamina_config.sprint_interval } else { PLAYER_MOVE_INTERVAL });
+    if move_timer.0.duration() != interval {
+        move_timer.0.set_duration(interval);
+    }
+
     move_timer.0.tick(time.delta());
 
+    if move_timer.0.finished() && input_buffer.0 == Vec2::ZERO {
+        if let Some(&(next_x, next_y)) = move_path.0.front() {
+            let grid_pos = query.single();
+            let (dx, dy) = (next_x - grid_pos.x, next_y - grid_pos.y);
+            // サーバーに戻されたなどで隣でなくなったら経路を捨てる
+            if dx.abs() > 1 || dy.abs() > 1 {
+                move_path.0.clear();
+            } else {
+                input_buffer.0 = Vec2::new(dx as f32, dy as f32);
+            }
+        }
+    }
+
     if move_timer.0.finished() {
         if input_buffer.0 != Vec2::ZERO {
             let mut grid_pos = query.single_mut();
@@ -43,6 +77,19 @@ pub fn move_player_tick(
             // ここにあった transform.translation = ... を削除しました。
             // これがあると一瞬でワープしてしまい、補間アニメーションが無効になります。
 
+            // 【新規】経路の次のマスに着いたら進める。着けなかったら（障害物が変わった等）やめる
+            if let Some(&next) = move_path.0.front() {
+                if next == (grid_pos.x, grid_pos.y) {
+                    move_path.0.pop_front();
+                } else {
+                    move_path.0.clear();
+                }
+            }
+
+            if sprinting {
+                stamina.0 = (stamina.0 - stamina_config.step_cost).max(0.0);
+            }
+
             // 入力を消費（リセット）して、キーを押し直すまで止まるようにする
             input_buffer.0 = Vec2::ZERO; 
         }
@@ -51,27 +98,44 @@ pub fn move_player_tick(
 
 // ==========================================
 // 【VISUAL LOGIC】 アニメーション同期システム
-// 役割: 毎フレーム実行。現在の Transform を GridPosition に向かって滑らかに移動させる。
+// 【変更】近づける計算は固定ティック (step_pixel_motion)、描画はティックの間の補間 (sync_player_pixel_pos) に分けた。
+// どちらも GridPosition は読むだけ
 // ==========================================
-pub fn sync_player_pixel_pos(
+
+// 補間スピード
+// 1.0秒間隔の移動なので、少しゆっくりめ(5.0〜10.0)にすると
+// 「歩いている」感じが出ます。大きくするとキビキビ動きます。
+const SMOOTH_SPEED: f32 = 10.0;
+
+// 【新規】これより遠くへ一度に動いたら（テレポートなど）補間せずにその場へ出す (マス)
+const SNAP_DISTANCE: f32 = 4.0;
+
+// FixedUpdate: 見た目の位置を GridPosition に向かって近づける（自分も他のプレイヤーも）
+pub fn step_pixel_motion(
     time: Res<Time>,
-    mut query: Query<(&GridPosition, &mut Transform), With<Player>>,
+    mut query: Query<(&GridPosition, &mut PixelMotion)>,
 ) {
-    let (grid_pos, mut transform) = query.single_mut();
+    let t = (time.delta_secs() * SMOOTH_SPEED).min(1.0);
+    for (grid_pos, mut motion) in &mut query {
+        let target = Vec2::new(grid_pos.x as f32 * TILE_SIZE, grid_pos.y as f32 * TILE_SIZE);
+        if motion.current.distance(target) > SNAP_DISTANCE * TILE_SIZE {
+            *motion = PixelMotion::at(grid_pos.x, grid_pos.y);
+            continue;
+        }
+        motion.previous = motion.current;
+        // Lerp (線形補間) で現在地から目的地へ近づける
+        // これにより、GridPositionが切り替わった瞬間に、キャラが「スーッ」と移動します
+        motion.current = motion.current.lerp(target, t);
+    }
+}
 
-    // 目標とするピクセル座標
-    let target_x = grid_pos.x as f32 * TILE_SIZE;
-    let target_y = grid_pos.y as f32 * TILE_SIZE;
+// 毎フレーム: 前のティックと今のティックの間を、ティックの進み具合で補間して Transform に入れる
+pub fn sync_player_pixel_pos(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&PixelMotion, &mut Transform), With<Player>>,
+) {
+    let Ok((motion, mut transform)) = query.get_single_mut() else { return };
     // Z座標は現在の値を維持（声のエフェクトなどが隠れないように）
-    let target_pos = Vec3::new(target_x, target_y, transform.translation.z);
-
-    // 補間スピード
-    // 1.0秒間隔の移動なので、少しゆっくりめ(5.0〜10.0)にすると
-    // 「歩いている」感じが出ます。大きくするとキビキビ動きます。
-    let smooth_speed = 10.0; 
-    let delta = time.delta_secs();
-
-    // Lerp (線形補間) で現在地から目的地へ近づける
-    // これにより、GridPositionが切り替わった瞬間に、キャラが「スーッ」と移動します
-    transform.translation = transform.translation.lerp(target_pos, delta * smooth_speed);
-}
\ No newline at end of file
+    let pos = motion.previous.lerp(motion.current, fixed_time.overstep_fraction());
+    transform.translation = pos.extend(transform.translation.z);
+}
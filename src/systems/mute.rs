@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use crate::components::GameEntity;
+use crate::resources::*;
+use crate::storage::Storage;
+use crate::controls::{Action, ActionState, Controls};
+
+// ==========================================
+// 【新規】ミュートしているプレイヤーの一覧
+// チャットメニューのキーを押しながらミュートのキーで開き、選んだ人のミュートを解く。
+// 近くにいない人（ログアウトした人など）もここから解除できる
+// ==========================================
+
+#[derive(Resource, Default)]
+pub struct MuteList {
+    pub is_open: bool,
+    selected: usize,
+}
+
+#[derive(Component)]
+pub struct MuteListDisplay;
+
+pub fn setup_mute_list(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut mute_list: ResMut<MuteList>,
+) {
+    *mute_list = MuteList::default();
+
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    commands.spawn((
+        Text::new(""),
+        TextFont { font: jp_font, font_size: 16.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            left: Val::Px(20.0),
+            display: Display::None,
+            padding: UiRect::all(Val::Px(12.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        MuteListDisplay,
+        BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.9)),
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(10.0)),
+        ZIndex(110),
+        GameEntity,
+    ));
+}
+
+// 開いている間はほかの入力を止める（PreUpdate、ワールドマップの後）
+pub fn handle_mute_list_input(
+    mut actions: ResMut<ActionState>,
+    mut mute_list: ResMut<MuteList>,
+    storage: Res<Storage>,
+    mut current_user: ResMut<CurrentUser>,
+    mut notification: ResMut<NotificationState>,
+) {
+    if !mute_list.is_open {
+        if actions.pressed(Action::OpenMenu) && actions.just_pressed(Action::Mute) {
+            mute_list.is_open = true;
+            mute_list.selected = 0;
+            actions.consume();
+        }
+        return;
+    }
+
+    let count = current_user.muted.len();
+    if actions.just_pressed(Action::Cancel) || actions.just_pressed(Action::Mute) {
+        mute_list.is_open = false;
+    } else if actions.just_pressed(Action::MoveUp) && count > 0 {
+        mute_list.selected = mute_list.selected.checked_sub(1).unwrap_or(count - 1);
+    } else if actions.just_pressed(Action::MoveDown) && count > 0 {
+        mute_list.selected = (mute_list.selected + 1) % count;
+    } else if actions.just_pressed(Action::Confirm) {
+        if let Some(name) = current_user.muted.get(mute_list.selected).cloned() {
+            notification.message = match storage.set_muted(&current_user.username, &name, false) {
+                Ok(()) => {
+                    current_user.muted.remove(mute_list.selected);
+                    mute_list.selected = mute_list.selected.min(count.saturating_sub(2));
+                    format!("Unmuted {}", name)
+                }
+                Err(e) => e,
+            };
+            notification.is_visible = true;
+            notification.timer.reset();
+        }
+    }
+    actions.consume();
+}
+
+pub fn update_mute_list_ui(
+    mute_list: Res<MuteList>,
+    current_user: Res<CurrentUser>,
+    controls: Res<Controls>,
+    mut query: Query<(&mut Text, &mut Node), With<MuteListDisplay>>,
+) {
+    let Ok((mut text, mut node)) = query.get_single_mut() else { return };
+    if !mute_list.is_open {
+        node.display = Display::None;
+        return;
+    }
+    node.display = Display::Flex;
+
+    let mut content = format!("Muted players ({})\n", current_user.muted.len());
+    if current_user.muted.is_empty() {
+        content.push_str("  (no one)\n");
+    }
+    for (i, name) in current_user.muted.iter().enumerate() {
+        let marker = if mute_list.selected == i { "▶" } else { " " };
+        content.push_str(&format!("{} {}\n", marker, name));
+    }
+    content.push_str(&format!(
+        "\n[{}] Unmute  [{}] Close",
+        controls.key_label(Action::Confirm),
+        controls.key_label(Action::Cancel),
+    ));
+    if text.0 != content {
+        text.0 = content;
+    }
+}
@@ -0,0 +1,332 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::constants::TILE_SIZE;
+use crate::events::{EmoteEvent, SpeakEvent};
+use crate::net::{ClientMessage, ServerMessage};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{database, net};
+#[cfg(target_arch = "wasm32")]
+use crate::net_web;
+use crate::storage::Storage;
+use crate::systems::account::enter_game;
+use crate::systems::avatar::spawn_avatar;
+use crate::systems::bot_memory::merge_bot_memory_rows;
+use crate::systems::leaderboard::Leaderboard;
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use std::sync::Mutex;
+
+// ==========================================
+// 【新規】ネットワーク対戦のクライアント側
+// 環境変数 GAME_SERVER (例: ws://localhost:9001) があればサーバーに接続する（ブラウザ版は常に接続）。
+// 【変更】接続中はログイン・セーブもサーバー経由 (Storage::Remote)。
+// 自分の移動はその場で反映し（予測）、サーバーに拒否されたら Correction で戻す。
+// 接続中はボットの位置もサーバーのものを使う（bot_wander_system は止める）
+// ==========================================
+
+// 【変更】保存先 (Storage) もここで決める
+// サーバーにつながればサーバー経由、そうでなければ今まで通り DB に直接つなぐ
+#[cfg(not(target_arch = "wasm32"))]
+pub fn setup_net_client(mut commands: Commands) {
+    let connection = std::env::var("GAME_SERVER").ok().and_then(|url| match net::connect(&url) {
+        Ok(connection) => {
+            println!("Connected to {}", url);
+            Some(connection)
+        }
+        // つながらなければ一人で遊ぶ
+        Err(e) => {
+            eprintln!("Server Connect Error ({}): {}", url, e);
+            None
+        }
+    });
+
+    match connection {
+        Some((outgoing, incoming)) => insert_net_client(&mut commands, outgoing, incoming),
+        None => commands.insert_resource(Storage::Local(DbPool(database::create_pool()))),
+    }
+}
+
+// 【新規】ブラウザ版は DB を使えないので、必ずページを配信したサーバーにつなぐ
+#[cfg(target_arch = "wasm32")]
+pub fn setup_net_client(mut commands: Commands) {
+    let connection = net_web::default_server_url()
+        .ok_or_else(|| "no page location".to_string())
+        .and_then(|url| net_web::connect(&url));
+
+    match connection {
+        Ok((outgoing, incoming)) => insert_net_client(&mut commands, outgoing, incoming),
+        // ログイン画面で "not connected" と出るように、相手のいないチャンネルを入れておく
+        Err(e) => {
+            web_sys::console::error_1(&format!("Server Connect Error: {}", e).into());
+            let (outgoing, _) = std::sync::mpsc::channel();
+            commands.insert_resource(Storage::Remote(outgoing));
+        }
+    }
+}
+
+fn insert_net_client(commands: &mut Commands, outgoing: Sender<ClientMessage>, incoming: Receiver<ServerMessage>) {
+    commands.insert_resource(Storage::Remote(outgoing.clone()));
+    commands.insert_resource(NetClient {
+        outgoing,
+        incoming: Mutex::new(incoming),
+        id: None,
+        synced_position: None,
+    });
+}
+
+// 【新規】ブラウザ版の送信（ソケットのスレッドがないので毎フレーム送る）
+#[cfg(target_arch = "wasm32")]
+pub fn flush_web_socket(mut commands: Commands, mut notification: ResMut<NotificationState>) {
+    if !net_web::flush_outgoing() {
+        notification.message = "Disconnected from server".to_string();
+        notification.is_visible = true;
+        notification.timer.reset();
+        commands.remove_resource::<NetClient>();
+    }
+}
+
+// 自分の移動・絵文字・言葉をサーバーに送る
+pub fn send_player_actions(
+    mut client: ResMut<NetClient>,
+    player_query: Query<&GridPosition, (With<Player>, Changed<GridPosition>)>,
+    mut emote_events: EventReader<EmoteEvent>,
+    mut speak_events: EventReader<SpeakEvent>,
+) {
+    if let Ok(pos) = player_query.get_single() {
+        if client.synced_position != Some((pos.x, pos.y)) {
+            client.synced_position = Some((pos.x, pos.y));
+            client.send(ClientMessage::Move { x: pos.x, y: pos.y });
+        }
+    }
+    for event in emote_events.read() {
+        client.send(ClientMessage::Emote { emoji: event.emoji.clone() });
+    }
+    for event in speak_events.read() {
+        client.send(ClientMessage::Say { word: event.word.clone() });
+    }
+}
+
+// 【変更】ログインの返事も受け取るので、ログイン画面でも動かす
+pub fn receive_server_messages(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut client: ResMut<NetClient>,
+    mut chat_log: ResMut<ChatLog>,
+    mut notification: ResMut<NotificationState>,
+    mut account_state: ResMut<AccountState>,
+    mut current_user: ResMut<CurrentUser>,
+    mut emoji_config: ResMut<EmojiConfig>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut memory_query: Query<(Entity, &BotSpawnPoint, &mut BotMemory), With<BotMemoryPending>>,
+    mut player_query: Query<&mut GridPosition, With<Player>>,
+    mut remote_query: Query<(Entity, &RemotePlayer, &mut GridPosition, &Children), Without<Player>>,
+    mut bot_query: Query<(&BotSpawnPoint, &mut GridPosition), (With<Bot>, Without<Player>, Without<RemotePlayer>)>,
+    mut text_query: Query<(&mut Text2d, &mut BotChatTimer, Has<RemotePlayerEmoji>)>,
+    mut waypoints: ResMut<Waypoints>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    let mut messages = Vec::new();
+    let mut disconnected = false;
+    {
+        let incoming = client.incoming.lock().unwrap();
+        loop {
+            match incoming.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    for message in messages {
+        match message {
+            ServerMessage::LoggedIn { id, profile } => {
+                client.id = Some(id);
+                client.synced_position = Some((profile.x, profile.y));
+                enter_game(&account_state, *profile, &mut current_user, &mut emoji_config, &mut notification, &mut next_state);
+            }
+            ServerMessage::LoginFailed { reason } => {
+                account_state.error_msg = reason;
+            }
+            ServerMessage::Correction { x, y } => {
+                set_player_position(&mut client, &mut player_query, x, y);
+            }
+            ServerMessage::Snapshot { players, bots } => {
+                let mut latest: HashMap<_, _> = players
+                    .into_iter()
+                    .filter(|p| Some(p.id) != client.id)
+                    .map(|p| (p.id, p))
+                    .collect();
+
+                for (entity, remote, mut pos, _) in &mut remote_query {
+                    match latest.remove(&remote.id) {
+                        Some(state) => {
+                            if pos.x != state.x || pos.y != state.y {
+                                pos.x = state.x;
+                                pos.y = state.y;
+                            }
+                        }
+                        // 見えなくなった・切断したプレイヤー
+                        None => commands.entity(entity).despawn_recursive(),
+                    }
+                }
+                for state in latest.into_values() {
+                    let entity = spawn_other_player(&mut commands, &asset_server, &state.name, state.x, state.y);
+                    commands.entity(entity).insert(RemotePlayer { id: state.id });
+                }
+
+                let bot_positions: HashMap<(i64, i64), (i64, i64)> = bots
+                    .into_iter()
+                    .map(|b| ((b.spawn_x, b.spawn_y), (b.x, b.y)))
+                    .collect();
+                for (spawn_point, mut pos) in &mut bot_query {
+                    if let Some(&(x, y)) = bot_positions.get(&(spawn_point.x, spawn_point.y)) {
+                        if pos.x != x || pos.y != y {
+                            pos.x = x;
+                            pos.y = y;
+                        }
+                    }
+                }
+            }
+            ServerMessage::Emote { id, emoji } => {
+                show_over_remote(&remote_query, &mut text_query, id, true, emoji);
+            }
+            ServerMessage::Chat { id, name, word } => {
+                // サーバーでも弾いているが、ミュート直後の行き違いがあるので念のため
+                if current_user.muted.contains(&name) {
+                    continue;
+                }
+                chat_log.messages.push((
+                    format!("{}: {}", name, word),
+                    Timer::new(std::time::Duration::from_secs(5), TimerMode::Once),
+                ));
+                show_over_remote(&remote_query, &mut text_query, id, false, word);
+            }
+            ServerMessage::BotMemories { bot_x, bot_y, rows } => {
+                let Some((entity, _, mut memory)) = memory_query
+                    .iter_mut()
+                    .find(|(_, spawn_point, _)| spawn_point.x == bot_x && spawn_point.y == bot_y)
+                else {
+                    continue;
+                };
+                merge_bot_memory_rows(&mut memory, rows);
+                commands.entity(entity).remove::<(BotMemoryPending, BotMemoryRequested)>();
+            }
+            // 【新規】ウェイポイントはサーバーが置いた位置に合わせる
+            ServerMessage::WaypointSaved { name, x, y } => {
+                let Some(waypoint) = waypoints.points.iter_mut().find(|w| w.name == name) else { continue };
+                if (waypoint.x, waypoint.y) != (x, y) {
+                    waypoint.x = x;
+                    waypoint.y = y;
+                    notification.message = format!("Saved {} at ({}, {})", name, x, y);
+                    notification.is_visible = true;
+                    notification.timer.reset();
+                }
+            }
+            ServerMessage::WaypointRejected { name, reason } => {
+                waypoints.points.retain(|w| w.name != name);
+                notification.message = format!("Couldn't save {}: {}", name, reason);
+                notification.is_visible = true;
+                notification.timer.reset();
+            }
+            ServerMessage::TeleportFailed { reason } => {
+                notification.message = format!("Teleport failed: {}", reason);
+                notification.is_visible = true;
+                notification.timer.reset();
+            }
+            ServerMessage::Leaderboard { rows } => {
+                leaderboard.rows = Some(rows);
+            }
+        }
+    }
+
+    if disconnected {
+        notification.message = "Disconnected from server".to_string();
+        notification.is_visible = true;
+        notification.timer.reset();
+        commands.remove_resource::<NetClient>();
+        for (entity, ..) in &remote_query {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn set_player_position(
+    client: &mut NetClient,
+    player_query: &mut Query<&mut GridPosition, With<Player>>,
+    x: i64,
+    y: i64,
+) {
+    client.synced_position = Some((x, y));
+    if let Ok(mut pos) = player_query.get_single_mut() {
+        pos.x = x;
+        pos.y = y;
+    }
+}
+
+// 他のプレイヤーの頭上に絵文字 (is_emoji) か言葉を出す
+fn show_over_remote(
+    remote_query: &Query<(Entity, &RemotePlayer, &mut GridPosition, &Children), Without<Player>>,
+    text_query: &mut Query<(&mut Text2d, &mut BotChatTimer, Has<RemotePlayerEmoji>)>,
+    id: crate::net::ClientId,
+    is_emoji: bool,
+    text: String,
+) {
+    let Some((.., children)) = remote_query.iter().find(|(_, remote, ..)| remote.id == id) else { return };
+    for &child in children.iter() {
+        if let Ok((mut bubble, mut timer, emoji_slot)) = text_query.get_mut(child) {
+            if emoji_slot == is_emoji {
+                bubble.0 = text.clone();
+                timer.0.reset();
+            }
+        }
+    }
+}
+
+// 【変更】presence でも使うので RemotePlayer は呼び出し側で付ける
+// 【変更】体・名前・絵文字は自分と同じ spawn_avatar で作り、吹き出しだけここで足す
+pub fn spawn_other_player(commands: &mut Commands, asset_server: &AssetServer, name: &str, x: i64, y: i64) -> Entity {
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+
+    let entity = spawn_avatar(
+        commands,
+        asset_server,
+        name,
+        x,
+        y,
+        0.6,
+        Color::srgb(0.9, 0.4, 0.0),
+        (RemotePlayerEmoji, BotChatTimer(Timer::from_seconds(3.0, TimerMode::Once))),
+    );
+    commands.entity(entity)
+        .insert(OtherPlayer { name: name.to_string() })
+        .with_children(|parent| {
+            // 言葉の吹き出し（ボットの BotChatText と同じく BotChatTimer で消える）
+            parent.spawn((
+                Text2d::new(""),
+                TextFont { font: jp_font, font_size: 20.0, ..default() },
+                TextColor(Color::BLACK),
+                TextLayout::new(JustifyText::Center, LineBreak::NoWrap),
+                Transform::from_xyz(0.0, TILE_SIZE * 0.8, 10.0),
+                RemotePlayerChat,
+                BotChatTimer(Timer::from_seconds(3.0, TimerMode::Once)),
+            ));
+        });
+    entity
+}
+
+// 他のプレイヤーの見た目を GridPosition に合わせる（自分と同じく補間する）
+// 【変更】近づける計算は固定ティックの step_pixel_motion、ここはティックの間の補間だけ
+pub fn sync_remote_player_pixel_pos(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&PixelMotion, &mut Transform), With<OtherPlayer>>,
+) {
+    let fraction = fixed_time.overstep_fraction();
+    for (motion, mut transform) in &mut query {
+        transform.translation = motion.previous.lerp(motion.current, fraction).extend(transform.translation.z);
+    }
+}
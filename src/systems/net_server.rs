@@ -0,0 +1,531 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::constants::{LEADERBOARD_SIZE, NET_VIEW_RANGE, PLAYER_MOVE_INTERVAL, VOICE_RANGE};
+use crate::database;
+use crate::map::{is_bot_spawn, is_item_spawn, item_index};
+use crate::pathfind::can_step;
+use crate::net::{self, BotState, ClientId, ClientMessage, PlayerState, ServerEvent, ServerMessage, UserProfile};
+use crate::spatial::SpatialIndex;
+use crate::chat::RateLimiter;
+use crate::explore::{ExploredMap, EXPLORE_RADIUS};
+use crate::quest::{Quest, QuestKind, QuestReward};
+use crate::stamina::StaminaConfig;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+// ==========================================
+// 【新規】サーバーモード (evol-server)
+// 役割: 接続してきたクライアントのプレイヤーを持ち、移動を判定し、
+// 周囲のプレイヤー・ボットの位置を定期的に送る。
+// ボットはプレイヤーの周りにだけ存在する（見た目なし、bot_wander_system で動く）
+// ==========================================
+
+// 通信の遅れを考えて、移動間隔は少しだけ短くても認める
+const MOVE_INTERVAL_TOLERANCE: f32 = 0.8;
+
+// 【新規】不正な移動を move_audit に記録する最短の間隔（秒）
+const AUDIT_INTERVAL: f64 = 1.0;
+
+// 【新規】クエストの達成を確かめるときの、話しかけの範囲の余裕（マス）
+const QUEST_RANGE_TOLERANCE: i64 = 1;
+
+// 【新規】操作の割り当ての JSON の上限（全部の操作を変えても収まる長さ）
+const MAX_BINDINGS_LEN: usize = 4096;
+
+pub fn setup_server(mut commands: Commands) {
+    let bind = std::env::var("SERVER_BIND").unwrap_or_else(|_| net::DEFAULT_BIND.to_string());
+    let events = net::start_server(&bind).expect("Failed to start server.");
+    println!("Listening on ws://{}", bind);
+
+    commands.insert_resource(DbPool(database::create_pool()));
+    commands.insert_resource(NetServer {
+        events: Mutex::new(events),
+        clients: HashMap::new(),
+        players: HashMap::new(),
+    });
+}
+
+// 1歩の移動として正しいか（クライアントの move_player_tick と同じく X → Y の順に判定）
+// 【変更】判定はクリック移動の経路探索と共通
+fn is_valid_step(from: &GridPosition, x: i64, y: i64) -> bool {
+    can_step((from.x, from.y), (x, y))
+}
+
+fn in_range(a: &GridPosition, b: &GridPosition, range: i64) -> bool {
+    (a.x - b.x).abs() <= range && (a.y - b.y).abs() <= range
+}
+
+// 【新規】記憶を書いてよいボットか（クライアントがボットを消すのは画面から離れてからなので、範囲は広めに取る）
+fn bot_memory_allowed(pos: &GridPosition, bot_x: i64, bot_y: i64) -> bool {
+    is_bot_spawn(bot_x, bot_y) && in_range(pos, &GridPosition { x: bot_x, y: bot_y }, NET_VIEW_RANGE * 2)
+}
+
+// 【新規】クエストの達成条件をサーバーの位置で確かめる（クライアントの handle_quest_speech / check_visit_quests と同じ条件）
+// ボットはクライアントの表示と少しずれるので、話しかけの範囲は QUEST_RANGE_TOLERANCE だけ広く取る
+fn quest_reached(
+    quest: &Quest,
+    player: &NetPlayer,
+    pos: &GridPosition,
+    bot_query: &Query<(&BotSpawnPoint, &GridPosition), (With<Bot>, Without<NetPlayer>)>,
+) -> bool {
+    let bot_near = |x: i64, y: i64| {
+        bot_query
+            .iter()
+            .any(|(spawn, bot_pos)| spawn.x == x && spawn.y == y && in_range(pos, bot_pos, VOICE_RANGE + QUEST_RANGE_TOLERANCE))
+    };
+    match &quest.kind {
+        QuestKind::Visit { x, y } => in_range(pos, &GridPosition { x: *x, y: *y }, 1),
+        QuestKind::Bring { .. } => bot_near(quest.giver_x, quest.giver_y),
+        QuestKind::SayWord { word, bot_x, bot_y } => player.words.contains(word) && bot_near(*bot_x, *bot_y),
+    }
+}
+
+pub fn receive_client_messages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut server: ResMut<NetServer>,
+    db_pool: Res<DbPool>,
+    stamina_config: Res<StaminaConfig>,
+    content: Res<ServerContent>,
+    mut player_query: Query<(&mut NetPlayer, &mut GridPosition)>,
+    bot_query: Query<(&BotSpawnPoint, &GridPosition), (With<Bot>, Without<NetPlayer>)>,
+) {
+    // ロック中に server を変更できないので先に取り出す
+    let events: Vec<ServerEvent> = server.events.lock().unwrap().try_iter().collect();
+    let now = time.elapsed_secs_f64();
+
+    for event in events {
+        match event {
+            ServerEvent::Connected(id, sender) => {
+                server.clients.insert(id, sender);
+            }
+            ServerEvent::Disconnected(id) => {
+                server.clients.remove(&id);
+                if let Some(entity) = server.players.remove(&id) {
+                    // 切断時の位置を保存する
+                    if let Ok((mut player, pos)) = player_query.get_mut(entity) {
+                        save_player(&db_pool, &mut player, &pos);
+                    }
+                    commands.entity(entity).despawn();
+                }
+            }
+            // 【変更】ログイン / アカウント作成。成功したらプレイヤーを出してセーブデータを返す
+            ServerEvent::Message(id, message @ (ClientMessage::Login { .. } | ClientMessage::Register { .. })) => {
+                if server.players.contains_key(&id) {
+                    continue;
+                }
+                match login(&db_pool, message) {
+                    Ok((username, profile)) => {
+                        println!("Client {} is {}", id, username);
+                        let mut explored = ExploredMap::from_chunks(profile.explored.clone(), profile.explored_counted.clone(), profile.explored_cells);
+                        explored.reveal(profile.x, profile.y, EXPLORE_RADIUS);
+                        let entity = commands.spawn((
+                            NetPlayer {
+                                id,
+                                username,
+                                last_move: f64::NEG_INFINITY,
+                                chat_limit: RateLimiter::default(),
+                                muted: profile.muted.iter().cloned().collect(),
+                                last_audit: f64::NEG_INFINITY,
+                                explored,
+                                stamina: stamina_config.max,
+                                stamina_at: now,
+                                last_saved: (profile.x, profile.y),
+                                emojis: profile.emojis.iter().cloned().collect(),
+                                words: profile.words.iter().cloned().collect(),
+                            },
+                            GridPosition { x: profile.x, y: profile.y },
+                        )).id();
+                        server.players.insert(id, entity);
+                        server.send(id, ServerMessage::LoggedIn { id, profile: Box::new(profile) });
+                    }
+                    // 失敗しても切断はしない（入力し直せるように）
+                    Err(reason) => server.send(id, ServerMessage::LoginFailed { reason }),
+                }
+            }
+            ServerEvent::Message(id, message) => {
+                let Some(&entity) = server.players.get(&id) else { continue };
+                let Ok((mut player, mut pos)) = player_query.get_mut(entity) else { continue };
+
+                match message {
+                    ClientMessage::Move { x, y } => {
+                        if x == pos.x && y == pos.y {
+                            continue;
+                        }
+                        // 【変更】歩きより速い移動は、スタミナが残っていればダッシュとして認める
+                        player.stamina = (player.stamina + (now - player.stamina_at) as f32 * stamina_config.regen_per_sec).min(stamina_config.max);
+                        player.stamina_at = now;
+                        let walk_ok = now - player.last_move >= f64::from(PLAYER_MOVE_INTERVAL * MOVE_INTERVAL_TOLERANCE);
+                        let sprint_ok = now - player.last_move >= f64::from(stamina_config.sprint_interval * MOVE_INTERVAL_TOLERANCE)
+                            && player.stamina >= stamina_config.step_cost * MOVE_INTERVAL_TOLERANCE;
+                        let interval_ok = walk_ok || sprint_ok;
+                        let violation = if !is_valid_step(&pos, x, y) {
+                            Some("invalid_step")
+                        } else if !interval_ok {
+                            Some("too_fast")
+                        } else {
+                            None
+                        };
+                        match violation {
+                            None => {
+                                pos.x = x;
+                                pos.y = y;
+                                player.last_move = now;
+                                if !walk_ok {
+                                    player.stamina = (player.stamina - stamina_config.step_cost).max(0.0);
+                                }
+                                player.explored.reveal(x, y, EXPLORE_RADIUS);
+                                // 【新規】アイテムのマスに乗ったら拾う（拾ったことのあるマスは DB が断る）
+                                if is_item_spawn(x, y) && !content.items.is_empty() {
+                                    let item = &content.items[item_index(x, y, content.items.len())];
+                                    log_save_error(&player.username, database::take_item(&db_pool.0, &player.username, x, y, &item.id).map(|_| ()));
+                                }
+                            }
+                            Some(kind) => {
+                                server.send(id, ServerMessage::Correction { x: pos.x, y: pos.y });
+                                // 【新規】不正な移動を記録する（AUDIT_INTERVAL に 1 件まで）
+                                if now - player.last_audit >= AUDIT_INTERVAL {
+                                    player.last_audit = now;
+                                    if let Err(e) = database::log_violation(&db_pool.0, &player.username, kind, (pos.x, pos.y), (x, y)) {
+                                        eprintln!("Audit Error ({}): {}", player.username, e);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    // 【変更】出してよい絵文字だけを、言葉と同じ連投制限をかけて中継する
+                    ClientMessage::Emote { emoji } => {
+                        if !content.allows_emoji(&emoji, &player.emojis) || !player.chat_limit.allow(now) {
+                            continue;
+                        }
+                        let sender_pos = *pos;
+                        broadcast_near(&server, &player_query, id, &sender_pos, NET_VIEW_RANGE, ServerMessage::Emote { id, emoji });
+                    }
+                    // 【変更】覚えていない言葉と連投は捨て、話し手をミュートしている人には届けない
+                    ClientMessage::Say { word } => {
+                        if !player.words.contains(&word) || !player.chat_limit.allow(now) {
+                            continue;
+                        }
+                        let sender_pos = *pos;
+                        let name = player.username.clone();
+                        for (listener, listener_pos) in player_query.iter() {
+                            if listener.id != id && in_range(&sender_pos, listener_pos, VOICE_RANGE) && !listener.muted.contains(&name) {
+                                server.send(listener.id, ServerMessage::Chat { id, name: name.clone(), word: word.clone() });
+                            }
+                        }
+                    }
+                    ClientMessage::SetMuted { target, muted } => {
+                        log_save_error(&player.username, database::set_muted(&db_pool.0, &player.username, &target, muted));
+                        if muted {
+                            player.muted.insert(target);
+                        } else {
+                            player.muted.remove(&target);
+                        }
+                    }
+                    // 【新規】操作の割り当て（中身はクライアントが解釈する。大きすぎるものは捨てる）
+                    ClientMessage::SaveBindings { bindings } => {
+                        if bindings.len() > MAX_BINDINGS_LEN {
+                            continue;
+                        }
+                        log_save_error(&player.username, database::save_bindings(&db_pool.0, &player.username, &bindings));
+                    }
+                    // 【新規】ウェイポイントは今のサーバー上の位置に置く
+                    // 【変更】結果を返す（クライアントは自分の位置で先に一覧に入れている）
+                    ClientMessage::AddWaypoint { name } => {
+                        match database::add_waypoint(&db_pool.0, &player.username, &name, pos.x, pos.y) {
+                            Ok(()) => server.send(id, ServerMessage::WaypointSaved { name, x: pos.x, y: pos.y }),
+                            Err(reason) => server.send(id, ServerMessage::WaypointRejected { name, reason }),
+                        }
+                    }
+                    ClientMessage::DeleteWaypoint { name } => {
+                        log_save_error(&player.username, database::delete_waypoint(&db_pool.0, &player.username, &name));
+                    }
+                    // 【新規】テレポート石の判定もサーバーの位置で行う。行き先は Correction で知らせる
+                    ClientMessage::Teleport { name } => {
+                        match database::teleport_to_waypoint(&db_pool.0, &player.username, &name, (pos.x, pos.y)) {
+                            Ok((x, y)) => {
+                                pos.x = x;
+                                pos.y = y;
+                                player.last_move = now;
+                                player.explored.reveal(x, y, EXPLORE_RADIUS);
+                                server.send(id, ServerMessage::Correction { x, y });
+                            }
+                            Err(reason) => server.send(id, ServerMessage::TeleportFailed { reason }),
+                        }
+                    }
+                    // 【新規】セーブ類。ユーザー名はログインしたもの、位置はサーバーのものを使う
+                    // 【変更】絵文字の割り当ては出してよい絵文字のときだけ書く
+                    ClientMessage::SaveProgress { s_key, d_key } => {
+                        let result = if content.allows_emoji(&s_key, &player.emojis) && content.allows_emoji(&d_key, &player.emojis) {
+                            database::save_user_keys(&db_pool.0, &player.username, pos.x, pos.y, &s_key, &d_key)
+                        } else {
+                            database::save_user_position(&db_pool.0, &player.username, pos.x, pos.y)
+                        };
+                        match result {
+                            Ok(None) => player.last_saved = (pos.x, pos.y),
+                            // 【新規】位置が認められなかったら DB の位置に戻す
+                            Ok(Some((x, y))) => {
+                                pos.x = x;
+                                pos.y = y;
+                                player.last_saved = (x, y);
+                                server.send(id, ServerMessage::Correction { x, y });
+                            }
+                            Err(e) => eprintln!("Save Error ({}): {}", player.username, e),
+                        }
+                    }
+                    // 【変更】内容はサーバーのコンテンツで作って保存する
+                    ClientMessage::AcceptQuest { giver_x, giver_y } => {
+                        let quest = content.quest_at(giver_x, giver_y);
+                        log_save_error(&player.username, database::accept_quest(&db_pool.0, &player.username, &quest));
+                    }
+                    // 【変更】受けたクエストを DB から読み、サーバーの位置で達成を確かめてから報酬を足す
+                    ClientMessage::CompleteQuest { giver_x, giver_y } => {
+                        let saved = match database::load_quest(&db_pool.0, &player.username, giver_x, giver_y) {
+                            Ok(Some(saved)) if !saved.done => saved,
+                            Ok(_) => continue,
+                            Err(e) => {
+                                eprintln!("Quest Load Error ({}): {}", player.username, e);
+                                continue;
+                            }
+                        };
+                        // 内容を保存する前に受けたクエストはサーバーのコンテンツで作り直す
+                        let quest = saved.quest.unwrap_or_else(|| content.quest_at(giver_x, giver_y));
+                        if !quest_reached(&quest, &player, &pos, &bot_query) {
+                            eprintln!("Quest Rejected ({}): ({}, {})", player.username, giver_x, giver_y);
+                            continue;
+                        }
+                        match database::complete_quest(&db_pool.0, &player.username, &quest) {
+                            Ok(true) => match quest.reward {
+                                QuestReward::Word(word) => {
+                                    player.words.insert(word);
+                                }
+                                QuestReward::Emoji(emoji) => {
+                                    player.emojis.insert(emoji);
+                                }
+                                QuestReward::Points(_) => {}
+                            },
+                            Ok(false) => eprintln!("Quest Rejected ({}): ({}, {})", player.username, giver_x, giver_y),
+                            Err(e) => eprintln!("Save Error ({}): {}", player.username, e),
+                        }
+                    }
+                    // 【変更】近くにいるボットの記憶だけ書く
+                    ClientMessage::SaveBotMemory { bot_x, bot_y, times_greeted, last_seen, affinity } => {
+                        if !bot_memory_allowed(&pos, bot_x, bot_y) {
+                            continue;
+                        }
+                        log_save_error(&player.username, database::save_bot_memory(
+                            &db_pool.0, bot_x, bot_y, &player.username, times_greeted, last_seen, affinity,
+                        ));
+                    }
+                    ClientMessage::AddBotMemory { bot_x, bot_y, times_greeted, last_seen, affinity } => {
+                        if !bot_memory_allowed(&pos, bot_x, bot_y) {
+                            continue;
+                        }
+                        log_save_error(&player.username, database::add_bot_memory(
+                            &db_pool.0, bot_x, bot_y, &player.username, times_greeted, last_seen, affinity,
+                        ));
+                    }
+                    // 【変更】ほかのプレイヤーの記憶は渡さない
+                    ClientMessage::LoadBotMemories { bot_x, bot_y } => {
+                        match database::load_bot_memories(&db_pool.0, bot_x, bot_y) {
+                            Ok(mut rows) => {
+                                rows.retain(|row| row.0 == player.username);
+                                server.send(id, ServerMessage::BotMemories { bot_x, bot_y, rows });
+                            }
+                            Err(e) => eprintln!("Bot Memory Load Error: {}", e),
+                        }
+                    }
+                    ClientMessage::LoadLeaderboard => {
+                        match database::load_leaderboard(&db_pool.0, LEADERBOARD_SIZE) {
+                            Ok(rows) => server.send(id, ServerMessage::Leaderboard { rows }),
+                            Err(e) => eprintln!("Leaderboard Load Error: {}", e),
+                        }
+                    }
+                    ClientMessage::Login { .. } | ClientMessage::Register { .. } => {}
+                }
+            }
+        }
+    }
+}
+
+// Login / Register を確かめてセーブデータを読む（エラーはクライアントに見せる文言）
+fn login(db_pool: &DbPool, message: ClientMessage) -> Result<(String, UserProfile), String> {
+    let pool = &db_pool.0;
+    let username = match message {
+        ClientMessage::Login { username, password } => match database::verify_user(pool, &username, &password) {
+            Ok(true) => username,
+            Ok(false) => return Err("Invalid User/Pass!".to_string()),
+            Err(e) => return Err(format!("DB Error: {}", e)),
+        },
+        ClientMessage::Register { username, password } => {
+            // クライアントのログイン画面と同じ制限
+            if !database::is_valid_credential(&username) || !database::is_valid_credential(&password) {
+                return Err("Invalid User/Pass!".to_string());
+            }
+            match database::user_exists(pool, &username) {
+                Ok(true) => return Err("User exists!".to_string()),
+                Ok(false) => database::create_user(pool, &username, &password).map_err(|e| format!("DB Error: {}", e))?,
+                Err(e) => return Err(format!("DB Err: {}", e)),
+            }
+            username
+        }
+        _ => return Err("Not logged in".to_string()),
+    };
+    // 【変更】ログアウトしていた時間は移動量の判定に数えない
+    database::start_session(pool, &username).map_err(|e| format!("DB Error: {}", e))?;
+    let profile = database::load_profile(pool, &username).map_err(|_| "Load Error".to_string())?;
+    Ok((username, profile))
+}
+
+fn log_save_error(username: &str, result: Result<(), String>) {
+    if let Err(e) = result {
+        eprintln!("Save Error ({}): {}", username, e);
+    }
+}
+
+// 【変更】位置と一緒に探索済みの場所も書く
+// 【変更】位置が認められなかったときは DB に残っている位置を返す
+fn save_player(db_pool: &DbPool, player: &mut NetPlayer, pos: &GridPosition) -> Option<(i64, i64)> {
+    let rejected = match database::save_user_position(&db_pool.0, &player.username, pos.x, pos.y) {
+        Ok(rejected) => {
+            player.last_saved = rejected.unwrap_or((pos.x, pos.y));
+            rejected
+        }
+        Err(e) => {
+            eprintln!("Position Save Error ({}): {}", player.username, e);
+            None
+        }
+    };
+    let chunks = player.explored.take_dirty();
+    let counted = player.explored.take_forgotten();
+    if !chunks.is_empty() || !counted.is_empty() {
+        if let Err(e) = database::save_explored(&db_pool.0, &player.username, &chunks, &counted, player.explored.cells) {
+            eprintln!("Explored Save Error ({}): {}", player.username, e);
+        }
+    }
+    rejected
+}
+
+// 【新規】接続中のプレイヤーの位置を定期的に書き込む（サーバーが落ちても大きく戻らないように）
+pub fn save_player_positions(
+    time: Res<Time>,
+    mut save_timer: ResMut<PositionSaveTimer>,
+    db_pool: Res<DbPool>,
+    server: Res<NetServer>,
+    mut player_query: Query<(&mut NetPlayer, &mut GridPosition)>,
+) {
+    save_timer.0.tick(time.delta());
+    if !save_timer.0.just_finished() {
+        return;
+    }
+
+    // 【変更】前回書いてから動いた人だけ（Changed はこのシステムの前回の実行からなので使えない）
+    for (mut player, mut pos) in &mut player_query {
+        if player.last_saved != (pos.x, pos.y) {
+            // 【新規】認められなかったら DB の位置に戻す
+            if let Some((x, y)) = save_player(&db_pool, &mut player, &pos) {
+                pos.x = x;
+                pos.y = y;
+                server.send(player.id, ServerMessage::Correction { x, y });
+            }
+        }
+    }
+}
+
+// 送り主以外で range マス以内にいるプレイヤーに送る
+fn broadcast_near(
+    server: &NetServer,
+    player_query: &Query<(&mut NetPlayer, &mut GridPosition)>,
+    from: ClientId,
+    pos: &GridPosition,
+    range: i64,
+    message: ServerMessage,
+) {
+    for (player, other_pos) in player_query.iter() {
+        if player.id != from && in_range(pos, other_pos, range) {
+            server.send(player.id, message.clone());
+        }
+    }
+}
+
+// プレイヤーの周りのボットを出す（クライアントの spawn_visible_bots に相当）
+pub fn spawn_server_bots(
+    mut commands: Commands,
+    player_query: Query<&GridPosition, With<NetPlayer>>,
+    bot_query: Query<&BotSpawnPoint, With<Bot>>,
+) {
+    let mut existing: HashSet<(i64, i64)> = bot_query.iter().map(|p| (p.x, p.y)).collect();
+    let mut rng = rand::thread_rng();
+
+    for pos in &player_query {
+        for x in pos.x - NET_VIEW_RANGE..=pos.x + NET_VIEW_RANGE {
+            for y in pos.y - NET_VIEW_RANGE..=pos.y + NET_VIEW_RANGE {
+                if is_bot_spawn(x, y) && existing.insert((x, y)) {
+                    commands.spawn((
+                        Bot,
+                        BotSpawnPoint { x, y },
+                        GridPosition { x, y },
+                        BotMoveTimer(Timer::from_seconds(rng.gen_range(1.0..3.0), TimerMode::Repeating)),
+                        BotTalking(Timer::from_seconds(0.0, TimerMode::Once)),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+// どのプレイヤーからも遠くなったボットを消す
+pub fn despawn_server_bots(
+    mut commands: Commands,
+    player_query: Query<&GridPosition, With<NetPlayer>>,
+    bot_query: Query<(Entity, &BotSpawnPoint), With<Bot>>,
+) {
+    for (entity, spawn_point) in &bot_query {
+        let spawn_pos = GridPosition { x: spawn_point.x, y: spawn_point.y };
+        if !player_query.iter().any(|pos| in_range(pos, &spawn_pos, NET_VIEW_RANGE * 2)) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// 各プレイヤーに、周囲 NET_VIEW_RANGE マスのプレイヤーとボットを送る
+pub fn send_snapshots(
+    time: Res<Time>,
+    mut timer: ResMut<SnapshotTimer>,
+    server: Res<NetServer>,
+    index: Res<SpatialIndex>,
+    player_query: Query<(&NetPlayer, &GridPosition)>,
+    bot_query: Query<(&BotSpawnPoint, &GridPosition), With<Bot>>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    for (receiver, pos) in &player_query {
+        let mut players = Vec::new();
+        let mut bots = Vec::new();
+
+        for entity in index.within((pos.x, pos.y), NET_VIEW_RANGE) {
+            if let Ok((player, player_pos)) = player_query.get(entity) {
+                players.push(PlayerState {
+                    id: player.id,
+                    name: player.username.clone(),
+                    x: player_pos.x,
+                    y: player_pos.y,
+                });
+            } else if let Ok((spawn_point, bot_pos)) = bot_query.get(entity) {
+                bots.push(BotState {
+                    spawn_x: spawn_point.x,
+                    spawn_y: spawn_point.y,
+                    x: bot_pos.x,
+                    y: bot_pos.y,
+                });
+            }
+        }
+
+        server.send(receiver.id, ServerMessage::Snapshot { players, bots });
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::constants::{NET_VIEW_RANGE, VOICE_RANGE};
+use crate::database::{self, PresenceRow};
+use crate::events::SpeakEvent;
+use crate::storage::Storage;
+use crate::systems::net_client::spawn_other_player;
+use std::collections::HashMap;
+
+// ==========================================
+// 【新規】プレゼンス（サーバーなしで他のプレイヤーを見る）
+// 同じ DB を使っているクライアント同士で、presence テーブルに
+// 位置・顔 (S キーの絵文字)・最後に話した言葉を定期的に書き、周囲の分を読む。
+// 更新が止まったプレイヤーは薄くなって消える。
+// DB に直接つないでいるとき (Storage::Local) だけ動く（サーバー接続中はスナップショットで見える）
+// ==========================================
+
+// この秒数更新がなければ薄くし始める
+const PRESENCE_STALE_SECS: f64 = 5.0;
+// この秒数更新がなければ消す
+const PRESENCE_EXPIRE_SECS: f64 = 15.0;
+
+pub fn sync_presence(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut timer: ResMut<PresenceTimer>,
+    storage: Res<Storage>,
+    current_user: Res<CurrentUser>,
+    emoji_config: Res<EmojiConfig>,
+    mut chat_log: ResMut<ChatLog>,
+    mut speak_events: EventReader<SpeakEvent>,
+    player_query: Query<&GridPosition, With<Player>>,
+    mut presence_query: Query<(Entity, &mut PresencePlayer, &mut GridPosition, &mut Sprite, &Children), Without<Player>>,
+    mut text_query: Query<(&mut Text2d, &mut TextColor, Option<&mut BotChatTimer>, Has<RemotePlayerEmoji>, Has<RemotePlayerChat>)>,
+) {
+    let Storage::Local(db_pool) = &*storage else { return };
+    let Ok(pos) = player_query.get_single() else { return };
+
+    // 話した言葉はすぐに書く（吹き出しが遅れないように）
+    let spoken = speak_events.read().last().map(|e| e.word.clone());
+    timer.0.tick(time.delta());
+    if spoken.is_none() && !timer.0.just_finished() {
+        return;
+    }
+
+    if let Err(e) = database::save_presence(
+        &db_pool.0,
+        &current_user.username,
+        pos.x,
+        pos.y,
+        &emoji_config.s_key,
+        spoken.as_deref(),
+    ) {
+        eprintln!("Presence Save Error: {}", e);
+    }
+
+    if !timer.0.just_finished() {
+        return;
+    }
+
+    let rows = match database::load_presence(&db_pool.0, &current_user.username, pos.x, pos.y, NET_VIEW_RANGE, PRESENCE_EXPIRE_SECS) {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Presence Load Error: {}", e);
+            return;
+        }
+    };
+    let mut latest: HashMap<String, PresenceRow> = rows.into_iter().map(|r| (r.username.clone(), r)).collect();
+
+    for (entity, mut presence, mut grid_pos, mut sprite, children) in &mut presence_query {
+        let Some(row) = latest.remove(&presence.username) else {
+            // 期限切れ・範囲外
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        if grid_pos.x != row.x || grid_pos.y != row.y {
+            grid_pos.x = row.x;
+            grid_pos.y = row.y;
+        }
+
+        let alpha = (1.0 - (row.age - PRESENCE_STALE_SECS) / (PRESENCE_EXPIRE_SECS - PRESENCE_STALE_SECS)).clamp(0.0, 1.0) as f32;
+        sprite.color.set_alpha(alpha);
+
+        // 【新規】言葉は声の届く範囲のものだけ、ミュートしていなければ受け取る
+        let audible = (row.x - pos.x).abs() <= VOICE_RANGE
+            && (row.y - pos.y).abs() <= VOICE_RANGE
+            && !current_user.muted.contains(&row.username);
+        let new_word = row.word.as_ref().filter(|_| audible && row.word_at > presence.word_at);
+        if let Some(word) = new_word {
+            chat_log.messages.push((
+                format!("{}: {}", row.username, word),
+                Timer::new(std::time::Duration::from_secs(5), TimerMode::Once),
+            ));
+        }
+        for &child in children.iter() {
+            let Ok((mut text, mut color, chat_timer, is_face, is_chat)) = text_query.get_mut(child) else { continue };
+            color.0.set_alpha(alpha);
+            // 顔は PlayerEmoji と同じ場所に出し続ける（タイマーで消えないように毎回リセット）
+            if is_face {
+                text.0 = row.face.clone();
+            } else if is_chat {
+                let Some(word) = new_word else { continue };
+                text.0 = word.clone();
+            } else {
+                continue;
+            }
+            if let Some(mut chat_timer) = chat_timer {
+                chat_timer.0.reset();
+            }
+        }
+        presence.word_at = row.word_at;
+    }
+
+    // 新しく見えたプレイヤー（古い言葉は吹き出しに出さない）
+    for row in latest.into_values() {
+        let entity = spawn_other_player(&mut commands, &asset_server, &row.username, row.x, row.y);
+        commands.entity(entity).insert(PresencePlayer { username: row.username, word_at: row.word_at });
+    }
+}
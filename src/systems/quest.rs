@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use crate::controls::{Action, ActionState, Controls};
+use crate::components::*;
+use crate::resources::*;
+use crate::storage::Storage;
+use crate::constants::BOT_HEARING_RADIUS;
+use crate::events::SpeakEvent;
+use crate::quest::{dialogue_for, generate_quest, Quest, QuestKind, QuestReward};
+use crate::spatial::SpatialIndex;
+
+// ==========================================
+// 【新規】クエスト
+// 受注: ボットに "Help" と話しかける
+// 進行: Bring = 依頼主にアイテムを持って話しかける
+//       Visit = 指定のマスの隣まで行く
+//       SayWord = 指定のボットに言葉を伝える
+// ==========================================
+
+// 報酬の反映先をまとめたもの
+struct RewardTarget<'a> {
+    vocab: &'a mut Vocabulary,
+    inventory: &'a mut Inventory,
+    points: &'a mut Points,
+    emoji_config: &'a mut EmojiConfig,
+}
+
+// 報酬を反映して DB に達成を記録する
+// 【変更】DB 側でも同じ報酬を足す（サーバー接続中はサーバーが達成を確かめてから足す）
+fn complete_quest(
+    quest: &Quest,
+    target: &mut RewardTarget,
+    username: &str,
+    storage: &Storage,
+    notification: &mut NotificationState,
+) -> bool {
+    if let QuestKind::Bring { item_id } = &quest.kind {
+        let Some(index) = target.inventory.items.iter().position(|i| i == item_id) else { return false };
+        target.inventory.items.remove(index);
+    }
+
+    match &quest.reward {
+        QuestReward::Word(word) => {
+            if !target.vocab.words.contains(word) {
+                target.vocab.words.push(word.clone());
+            }
+        }
+        QuestReward::Emoji(emoji) => {
+            if !target.emoji_config.unlocked.contains(emoji) {
+                target.emoji_config.unlocked.push(emoji.clone());
+            }
+        }
+        QuestReward::Points(points) => target.points.0 += points,
+    }
+
+    if let Err(e) = storage.complete_quest(username, quest) {
+        eprintln!("Quest Save Error: {}", e);
+    }
+
+    notification.message = format!("Quest Complete!\nGot {}", quest.reward_text());
+    notification.is_visible = true;
+    notification.timer.reset();
+    true
+}
+
+fn set_bubble(
+    children: &Children,
+    bot_text_query: &mut Query<(&mut Text2d, &mut BotChatTimer), With<BotChatText>>,
+    text: String,
+) {
+    for &child in children.iter() {
+        if let Ok((mut bubble, mut timer)) = bot_text_query.get_mut(child) {
+            bubble.0 = text.clone();
+            timer.0.reset();
+        }
+    }
+}
+
+// 話しかけたときのクエスト処理（受注・Bring・SayWord）
+// bot_respond_to_words の後に実行して、吹き出しをクエストの内容で上書きする
+pub fn handle_quest_speech(
+    mut speak_events: EventReader<SpeakEvent>,
+    current_user: Res<CurrentUser>,
+    storage: Res<Storage>,
+    item_table: Res<ItemTable>,
+    mut quest_log: ResMut<QuestLog>,
+    mut emoji_config: ResMut<EmojiConfig>,
+    mut notification: ResMut<NotificationState>,
+    mut player_query: Query<(&mut Vocabulary, &mut Inventory, &mut Points), With<Player>>,
+    index: Res<SpatialIndex>,
+    // 【変更】クエストはボットが話すセリフ (BotDialogueText) から作る
+    mut bot_query: Query<(&BotSpawnPoint, &Children, &mut BotTalking, &BotDialogueText), With<Bot>>,
+    mut bot_text_query: Query<(&mut Text2d, &mut BotChatTimer), With<BotChatText>>,
+) {
+    // 何も話していないフレームでは Vocabulary などを変更扱いにしない
+    if speak_events.is_empty() { return; }
+    let Ok((mut vocab, mut inventory, mut points)) = player_query.get_single_mut() else { return };
+    let mut target = RewardTarget {
+        vocab: &mut vocab,
+        inventory: &mut inventory,
+        points: &mut points,
+        emoji_config: &mut emoji_config,
+    };
+
+    for event in speak_events.read() {
+        for entity in index.within_radius((event.x, event.y), BOT_HEARING_RADIUS) {
+            let Ok((spawn_point, children, mut talking, dialogue)) = bot_query.get_mut(entity) else { continue };
+
+            let mut bubble = None;
+
+            // 進行中のクエストの達成判定
+            for entry in quest_log.entries.iter_mut().filter(|e| !e.done) {
+                let achieved = match &entry.quest.kind {
+                    QuestKind::Bring { item_id } => {
+                        entry.quest.giver_x == spawn_point.x
+                            && entry.quest.giver_y == spawn_point.y
+                            && target.inventory.items.contains(item_id)
+                    }
+                    QuestKind::SayWord { word, bot_x, bot_y } => {
+                        *bot_x == spawn_point.x && *bot_y == spawn_point.y && *word == event.word
+                    }
+                    QuestKind::Visit { .. } => false,
+                };
+                if achieved && complete_quest(&entry.quest, &mut target, &current_user.username, &storage, &mut notification) {
+                    entry.done = true;
+                    bubble = Some("ありがとう！".to_string());
+                }
+            }
+
+            // "Help" でクエストを受注（受注済みならもう一度内容を言う）
+            if bubble.is_none() && event.word == "Help" {
+                bubble = Some(match quest_log.find(spawn_point.x, spawn_point.y) {
+                    Some(entry) if entry.done => "この前はありがとう！".to_string(),
+                    Some(entry) => entry.quest.offer_text(&item_table.items),
+                    None => {
+                        let quest = generate_quest(spawn_point.x, spawn_point.y, &dialogue.0, &item_table.items);
+                        if let Err(e) = storage.accept_quest(&current_user.username, &quest) {
+                            eprintln!("Quest Save Error: {}", e);
+                        }
+                        let offer = quest.offer_text(&item_table.items);
+                        notification.message = format!("New Quest!\n{}", quest.log_text(&item_table.items));
+                        notification.is_visible = true;
+                        notification.timer.reset();
+                        quest_log.entries.push(QuestEntry { quest, done: false });
+                        offer
+                    }
+                });
+            }
+
+            if let Some(text) = bubble {
+                talking.0.set_duration(std::time::Duration::from_secs(5));
+                talking.0.reset();
+                set_bubble(children, &mut bot_text_query, text);
+            }
+        }
+    }
+}
+
+// Visit クエスト: 目的地の隣（縦横1マス以内）まで来たら達成
+pub fn check_visit_quests(
+    current_user: Res<CurrentUser>,
+    storage: Res<Storage>,
+    mut quest_log: ResMut<QuestLog>,
+    mut emoji_config: ResMut<EmojiConfig>,
+    mut notification: ResMut<NotificationState>,
+    mut player_query: Query<(&GridPosition, &mut Vocabulary, &mut Inventory, &mut Points), (With<Player>, Changed<GridPosition>)>,
+) {
+    let Ok((pos, mut vocab, mut inventory, mut points)) = player_query.get_single_mut() else { return };
+    let mut target = RewardTarget {
+        vocab: &mut vocab,
+        inventory: &mut inventory,
+        points: &mut points,
+        emoji_config: &mut emoji_config,
+    };
+
+    for entry in quest_log.entries.iter_mut().filter(|e| !e.done) {
+        if let QuestKind::Visit { x, y } = entry.quest.kind {
+            if (pos.x - x).abs() <= 1
+                && (pos.y - y).abs() <= 1
+                && complete_quest(&entry.quest, &mut target, &current_user.username, &storage, &mut notification)
+            {
+                entry.done = true;
+            }
+        }
+    }
+}
+
+// ログイン時に読み込んだクエストからクエストログを作る
+// 【変更】保存した内容を使う（内容を保存する前に受けたものだけ座標から作り直す）
+pub fn setup_quest_log(
+    mut quest_log: ResMut<QuestLog>,
+    current_user: Res<CurrentUser>,
+    bot_dialogues: Res<BotDialogues>,
+    item_table: Res<ItemTable>,
+) {
+    quest_log.entries = current_user
+        .quests
+        .iter()
+        .map(|saved| QuestEntry {
+            quest: saved.quest.clone().unwrap_or_else(|| {
+                let dialogue = dialogue_for(saved.giver_x, saved.giver_y, &bot_dialogues.lines);
+                generate_quest(saved.giver_x, saved.giver_y, &dialogue, &item_table.items)
+            }),
+            done: saved.done,
+        })
+        .collect();
+}
+
+pub fn update_quest_log_ui(
+    actions: Res<ActionState>,
+    controls: Res<Controls>,
+    mut quest_log: ResMut<QuestLog>,
+    item_table: Res<ItemTable>,
+    player_query: Query<(&Inventory, &Points), With<Player>>,
+    mut query: Query<(&mut Text, &mut Node), With<QuestLogDisplay>>,
+) {
+    let Ok((mut text, mut node)) = query.get_single_mut() else { return };
+
+    if actions.just_pressed(Action::QuestLog) {
+        quest_log.is_open = !quest_log.is_open;
+    }
+    if !quest_log.is_open {
+        node.display = Display::None;
+        return;
+    }
+    node.display = Display::Flex;
+
+    let Ok((inventory, points)) = player_query.get_single() else { return };
+
+    let mut content = format!("Quests  (Points: {})\n\n", points.0);
+    let active: Vec<&QuestEntry> = quest_log.entries.iter().filter(|e| !e.done).collect();
+    if active.is_empty() {
+        content.push_str("No active quests.\nSay \"Help\" to a bot!\n");
+    }
+    for entry in active {
+        content.push_str(&format!(
+            "- {}\n   reward: {}\n",
+            entry.quest.log_text(&item_table.items),
+            entry.quest.reward_text()
+        ));
+    }
+    let done_count = quest_log.entries.iter().filter(|e| e.done).count();
+    content.push_str(&format!("\nCompleted: {}\n", done_count));
+
+    content.push_str("\nItems: ");
+    if inventory.items.is_empty() {
+        content.push_str("(none)");
+    } else {
+        let names: Vec<String> = inventory
+            .items
+            .iter()
+            .map(|id| item_table.get(id).map(|item| item.name.clone()).unwrap_or_else(|| id.clone()))
+            .collect();
+        content.push_str(&names.join(", "));
+    }
+    content.push_str(&format!("\n\n[{}] Close", controls.key_label(Action::QuestLog)));
+
+    text.0 = content;
+}
@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+use crate::components::GridPosition;
+use crate::spatial::SpatialIndex;
+
+// ==========================================
+// 空間インデックスの更新
+// 役割: GridPosition の追加・変更・削除 (デスポーン含む) をインデックスに反映する。
+// 移動処理の側で先に set している場合もあるが、set は同じ位置なら何もしない
+// ==========================================
+pub fn update_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    changed_query: Query<(Entity, &GridPosition), Changed<GridPosition>>,
+    mut removed: RemovedComponents<GridPosition>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for (entity, pos) in &changed_query {
+        index.set(entity, (pos.x, pos.y));
+    }
+}
@@ -2,75 +2,55 @@ use bevy::prelude::*;
 use crate::constants::*;
 use crate::components::*;
 use crate::resources::*;
-use crate::database;
-use r2d2_postgres::PostgresConnectionManager;
-use postgres::NoTls;
-use std::fs;
-use std::env; // 環境変数読み込み用
+use crate::stamina::StaminaConfig;
+use crate::systems::avatar::spawn_avatar;
 
 pub fn setup(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
 ) {
-    // 【修正】環境変数 DB_HOST があれば使い、なければ localhost を使う
-    // Docker内では "db"、ローカル開発では "localhost" になります
-    let db_host = env::var("DB_HOST").unwrap_or_else(|_| "localhost".to_string());
-    
-    // 接続文字列を動的に生成
-    let db_url = format!("host={} user=postgres password=password dbname=postgres", db_host);
-
-    println!("Connecting to DB at: {}", db_host);
-
-    let manager = PostgresConnectionManager::new(
-        db_url.parse().unwrap(),
-        NoTls,
-    );
-    
-    // 接続待機リトライロジックがないため、コンテナ起動順によっては即死する可能性がありますが
-    // restart: alwaysを入れているので再起動してつながります。
-    let pool = r2d2::Pool::new(manager).expect("Failed to create DB pool.");
-    
-    if let Err(e) = database::init_db(&pool) {
-        eprintln!("DB Init Error: {}", e);
-    }
-    
-    commands.insert_resource(DbPool(pool));
+    // 【変更】DB への接続 (Storage) は setup_net_client が用意する
     commands.spawn(Camera2d);
 
-    // セリフ読み込み
-    let mut dialogues = BotDialogues::default();
-    // Docker内パスに対応するため、相対パスはそのまま使用（WORKDIRが/appなのでOK）
-    match fs::read_to_string("assets/bot_dialogues.txt") {
-        Ok(content) => {
-            dialogues.lines = content.lines().map(|s| s.to_string()).collect();
-            println!("Loaded {} lines of dialogue.", dialogues.lines.len());
-        }
-        Err(e) => {
-            eprintln!("Failed to load bot dialogues: {}", e);
-            dialogues.lines.push("No dialogues found.".to_string());
-        }
-    }
-    commands.insert_resource(dialogues);
+    // 【変更】セリフ・絵文字リストはアセットとして読み込む
+    // 変更の反映は apply_content_updates が担当（ファイル監視でホットリロード）
+    commands.insert_resource(ContentHandles {
+        bot_dialogues: asset_server.load("bot_dialogues.txt"),
+        emoji_list: asset_server.load("emoji_list.txt"),
+        item_table: asset_server.load("item_table.txt"),
+    });
 }
 
 pub fn setup_game(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     current_user: Res<CurrentUser>,
+    stamina_config: Res<StaminaConfig>,
 ) {
     let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
     let emoji_font = asset_server.load("fonts/NotoEmoji-Bold.ttf");
 
-    commands.spawn((
+    // 【変更】体と絵文字は画面の真ん中の UI ではなく、ワールドに置く（他のプレイヤーと同じ spawn_avatar）
+    let player = spawn_avatar(
+        &mut commands,
+        &asset_server,
+        &current_user.username,
+        current_user.grid_x,
+        current_user.grid_y,
+        0.7,
+        PLAYER_COLOR,
+        (PlayerEmoji, EmojiTimer(Timer::from_seconds(3.0, TimerMode::Once))),
+    );
+    commands.entity(player).insert((
         Player,
-        Transform::from_xyz(0.0, 0.0, 0.0), 
-        GridPosition { 
-            x: current_user.grid_x, 
-            y: current_user.grid_y 
-        },
+        Stamina(stamina_config.max),
         Vocabulary {
             words: current_user.words.clone(),
         },
-        GameEntity,
+        Inventory {
+            items: current_user.items.clone(),
+        },
+        Points(current_user.points),
     ))
     .with_children(|parent| {
         let radius = 4;
@@ -95,34 +75,6 @@ pub fn setup_game(
         });
     });
 
-    commands.spawn(Node {
-        width: Val::Px(TILE_SIZE * 0.9),
-        height: Val::Px(TILE_SIZE * 0.9),
-        position_type: PositionType::Absolute,
-        left: Val::Percent(50.0),
-        top: Val::Percent(50.0),
-        margin: UiRect {
-            left: Val::Px(-(TILE_SIZE * 0.9) / 2.0),
-            top: Val::Px(-(TILE_SIZE * 0.9) / 2.0),
-            ..default()
-        },
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    })
-    .insert(BackgroundColor(PLAYER_COLOR))
-    .insert(GameEntity)
-    .with_children(|parent| {
-        parent.spawn((
-            Text::new(""), 
-            TextFont { font: emoji_font.clone(), font_size: 30.0, ..default() },
-            TextColor(Color::WHITE), 
-            TextLayout::new(JustifyText::Center, LineBreak::NoWrap), 
-            PlayerEmoji, 
-            EmojiTimer(Timer::from_seconds(3.0, TimerMode::Once)),
-        ));
-    });
-
     commands.spawn((
         Text::new("(0, 0)"),
         TextFont { font: jp_font.clone(), font_size: 24.0, ..default() },
@@ -137,6 +89,33 @@ pub fn setup_game(
         GameEntity,
     ));
 
+    // 【新規】スタミナバー（座標の上）
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(56.0),
+            left: Val::Px(20.0),
+            width: Val::Px(120.0),
+            height: Val::Px(8.0),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.2)),
+        BorderColor(Color::BLACK),
+        GameEntity,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.2, 0.7, 0.3)),
+            StaminaBar,
+        ));
+    });
+
     commands.spawn((
         Text::new("."),
         TextFont { font: jp_font.clone(), font_size: 40.0, ..default() },
@@ -185,6 +164,27 @@ pub fn setup_game(
         GameEntity,
     ));
 
+    // 【新規】クエストログ
+    commands.spawn((
+        Text::new(""),
+        TextFont { font: jp_font.clone(), font_size: 18.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(80.0),
+            right: Val::Px(20.0),
+            display: Display::None,
+            padding: UiRect::all(Val::Px(15.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        QuestLogDisplay,
+        BackgroundColor(Color::srgba(0.3, 0.2, 0.0, 0.9)),
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(10.0)),
+        GameEntity,
+    ));
+
     commands.spawn((
         Text::new(""),
         TextFont { font: emoji_font.clone(), font_size: 30.0, ..default() },
@@ -0,0 +1,300 @@
+use bevy::prelude::*;
+use crate::components::*;
+use crate::resources::*;
+use crate::systems::minimap::MinimapDisplay;
+use crate::controls::{Action, ActionState};
+
+// ==========================================
+// 【新規】タッチ操作（スマホ・タブレット）
+// 画面のボタンを押している間、対応する操作 (Action) を押したことにする。
+// 絵文字・言葉・メニューは今までの入力のシステムがそのまま処理する。
+// 【変更】キーではなく操作に変えるので、割り当てを変えてもタッチのボタンはそのまま使える。
+// キーボードの状態 (ButtonInput<KeyCode>) には書き込まない。
+// 【変更】十字キーは移動の入力 (InputBuffer) に直接書く。メニューが開いている間だけ上下左右の操作にして、選択を動かす。
+// チャットボタンは OpenMenu の長押しの代わりで、押すたびに開く / 閉じるを切り替える。
+// 最初にタッチされたときに表示し、縦長 / 横長で配置を変える
+// ==========================================
+
+const BUTTON_SIZE: f32 = 48.0;
+const BUTTON_GAP: f32 = 6.0;
+const MARGIN: f32 = 20.0;
+
+const BUTTON_COLOR: Color = Color::srgba(0.1, 0.1, 0.1, 0.5);
+const BUTTON_PRESSED_COLOR: Color = Color::srgba(0.2, 0.5, 1.0, 0.8);
+
+// 押している間その操作を押したことにする
+#[derive(Component)]
+pub struct TouchButton(pub Action);
+
+// OpenMenu（チャットメニュー）の切り替え
+#[derive(Component)]
+pub struct TouchChatToggle;
+
+// 【新規】十字キーのボタン（押している向き）
+#[derive(Component)]
+pub struct TouchDpadButton(pub Action, pub Vec2);
+
+#[derive(Component)]
+pub struct TouchDpad;
+
+#[derive(Component)]
+pub struct TouchActions;
+
+// ボタンの文字（絵文字・言葉が変わったら書き換える）
+#[derive(Component)]
+pub struct TouchLabel(pub Action);
+
+#[derive(Resource, Default)]
+pub struct TouchState {
+    pub enabled: bool,
+    pub chat_open: bool,
+    // 最後に配置したときの向き (true = 縦長)
+    portrait: Option<bool>,
+}
+
+fn button_node() -> Node {
+    Node {
+        width: Val::Px(BUTTON_SIZE),
+        height: Val::Px(BUTTON_SIZE),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+pub fn setup_touch_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut touch_state: ResMut<TouchState>,
+) {
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    let emoji_font = asset_server.load("fonts/NotoEmoji-Bold.ttf");
+    let (dpad_display, actions_display) = if touch_state.enabled { (Display::Grid, Display::Flex) } else { (Display::None, Display::None) };
+    // 配置はレイアウトのシステムでやり直す
+    touch_state.portrait = None;
+
+    // 十字キー（3x3 の真ん中と四隅は空き）
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            display: dpad_display,
+            grid_template_columns: RepeatedGridTrack::px(3, BUTTON_SIZE),
+            grid_template_rows: RepeatedGridTrack::px(3, BUTTON_SIZE),
+            column_gap: Val::Px(BUTTON_GAP),
+            row_gap: Val::Px(BUTTON_GAP),
+            ..default()
+        },
+        ZIndex(150),
+        TouchDpad,
+        GameEntity,
+    ))
+    .with_children(|parent| {
+        let cells = [None, Some((Action::MoveUp, Vec2::Y, "▲")), None,
+                     Some((Action::MoveLeft, Vec2::NEG_X, "◀")), None, Some((Action::MoveRight, Vec2::X, "▶")),
+                     None, Some((Action::MoveDown, Vec2::NEG_Y, "▼")), None];
+        for cell in cells {
+            match cell {
+                Some((action, dir, label)) => {
+                    parent.spawn((Button, button_node(), BackgroundColor(BUTTON_COLOR), BorderRadius::all(Val::Px(8.0)), TouchDpadButton(action, dir)))
+                        .with_children(|b| {
+                            b.spawn((Text::new(label), TextFont { font: jp_font.clone(), font_size: 22.0, ..default() }, TextColor(Color::WHITE)));
+                        });
+                }
+                None => {
+                    parent.spawn(button_node());
+                }
+            }
+        }
+    });
+
+    // 絵文字・チャット・OK / 言葉・Back の 2 段
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            display: actions_display,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(BUTTON_GAP),
+            ..default()
+        },
+        ZIndex(150),
+        TouchActions,
+        GameEntity,
+    ))
+    .with_children(|parent| {
+        let rows = [
+            [Action::EmoteThumb, Action::EmoteS, Action::EmoteD, Action::OpenMenu, Action::Confirm],
+            [Action::Speak(0), Action::Speak(1), Action::Speak(2), Action::Speak(3), Action::Cancel],
+        ];
+        for row in rows {
+            parent.spawn(Node { column_gap: Val::Px(BUTTON_GAP), ..default() })
+                .with_children(|row_parent| {
+                    for action in row {
+                        let is_emoji = matches!(action, Action::EmoteThumb | Action::EmoteS | Action::EmoteD | Action::OpenMenu);
+                        let font = if is_emoji { emoji_font.clone() } else { jp_font.clone() };
+                        let font_size = if is_emoji { 24.0 } else { 12.0 };
+                        let mut button = row_parent.spawn((Button, button_node(), BackgroundColor(BUTTON_COLOR), BorderRadius::all(Val::Px(8.0))));
+                        // OpenMenu は押している間ではなく切り替え
+                        if action == Action::OpenMenu {
+                            button.insert(TouchChatToggle);
+                        } else {
+                            button.insert(TouchButton(action));
+                        }
+                        button.with_children(|b| {
+                            b.spawn((
+                                Text::new(""),
+                                TextFont { font, font_size, ..default() },
+                                TextColor(Color::WHITE),
+                                TextLayout::new_with_justify(JustifyText::Center),
+                                TouchLabel(action),
+                            ));
+                        });
+                    }
+                });
+        }
+    });
+}
+
+// タッチされたらタッチ操作を出す（一度出したら出しっぱなし）
+pub fn detect_touch(
+    touches: Res<Touches>,
+    mut touch_state: ResMut<TouchState>,
+    mut query: Query<(&mut Node, Has<TouchDpad>), Or<(With<TouchDpad>, With<TouchActions>)>>,
+) {
+    if touch_state.enabled || touches.iter_just_pressed().next().is_none() {
+        return;
+    }
+    touch_state.enabled = true;
+    for (mut node, is_dpad) in &mut query {
+        node.display = if is_dpad { Display::Grid } else { Display::Flex };
+    }
+}
+
+// 押されているボタンの操作を押したことにする（PreUpdate の UI の判定の後、ActionState の更新より前）
+// 【変更】十字キーは InputBuffer に書く（FixedUpdate の移動がこのフレームのうちに使う）
+pub fn apply_touch_buttons(
+    mut actions: ResMut<ActionState>,
+    mut touch_state: ResMut<TouchState>,
+    mut input_buffer: ResMut<InputBuffer>,
+    mut move_path: ResMut<MovePath>,
+    emoji_state: Res<EmojiSelectState>,
+    chat_menu_state: Res<ChatMenuState>,
+    mut button_query: Query<(&Interaction, &TouchButton, &mut BackgroundColor)>,
+    mut dpad_query: Query<(&Interaction, &TouchDpadButton, &mut BackgroundColor), Without<TouchButton>>,
+    mut chat_query: Query<(Ref<Interaction>, &mut BackgroundColor), (With<TouchChatToggle>, Without<TouchButton>, Without<TouchDpadButton>)>,
+) {
+    for (interaction, mut color) in &mut chat_query {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            touch_state.chat_open = !touch_state.chat_open;
+        }
+        color.set_if_neq(BackgroundColor(if touch_state.chat_open { BUTTON_PRESSED_COLOR } else { BUTTON_COLOR }));
+    }
+
+    for (interaction, button, mut color) in &mut button_query {
+        let pressed = *interaction == Interaction::Pressed;
+        color.set_if_neq(BackgroundColor(if pressed { BUTTON_PRESSED_COLOR } else { BUTTON_COLOR }));
+        if pressed {
+            actions.injected.insert(button.0);
+        }
+    }
+    if touch_state.chat_open {
+        actions.injected.insert(Action::OpenMenu);
+    }
+
+    // メニュー・ワールドマップなどが開いていれば（前のフレームで入力が止められていれば）選択を動かす
+    let in_menu = touch_state.chat_open || chat_menu_state.is_open || emoji_state.is_open || actions.consumed();
+    let mut direction = Vec2::ZERO;
+    for (interaction, button, mut color) in &mut dpad_query {
+        let pressed = *interaction == Interaction::Pressed;
+        color.set_if_neq(BackgroundColor(if pressed { BUTTON_PRESSED_COLOR } else { BUTTON_COLOR }));
+        if !pressed {
+            continue;
+        }
+        if in_menu {
+            actions.injected.insert(button.0);
+        } else {
+            direction += button.1;
+        }
+    }
+    if direction != Vec2::ZERO {
+        input_buffer.0 = direction;
+        // キーと同じく、十字キーで動かしたらクリック移動はやめる
+        move_path.0.clear();
+    }
+}
+
+// 絵文字と言葉が変わったらボタンの文字を書き換える
+pub fn update_touch_labels(
+    touch_state: Res<TouchState>,
+    emoji_config: Res<EmojiConfig>,
+    player_query: Query<&Vocabulary, With<Player>>,
+    mut label_query: Query<(&mut Text, &TouchLabel)>,
+) {
+    if !touch_state.enabled {
+        return;
+    }
+    let words = player_query.get_single().map(|v| v.words.as_slice()).unwrap_or(&[]);
+    for (mut text, label) in &mut label_query {
+        let new_text = match label.0 {
+            Action::EmoteThumb => "👍".to_string(),
+            Action::EmoteS => emoji_config.s_key.clone(),
+            Action::EmoteD => emoji_config.d_key.clone(),
+            Action::OpenMenu => "💬".to_string(),
+            Action::Confirm => "OK".to_string(),
+            Action::Cancel => "Back".to_string(),
+            Action::Speak(n) => words.get(n as usize).cloned().unwrap_or_default(),
+            _ => continue,
+        };
+        if text.0 != new_text {
+            text.0 = new_text;
+        }
+    }
+}
+
+// 横長: 言葉・絵文字は左下、十字キーは右下
+// 縦長: 言葉・絵文字は下に、十字キーはその上の右側
+// どちらも SAVE ボタン・チャットメニューはボタンの上に、ミニマップは右上に移す
+pub fn layout_touch_controls(
+    mut touch_state: ResMut<TouchState>,
+    window_query: Query<&Window>,
+    mut dpad_query: Query<&mut Node, With<TouchDpad>>,
+    mut actions_query: Query<&mut Node, (With<TouchActions>, Without<TouchDpad>)>,
+    mut others_query: Query<
+        (&mut Node, Has<SaveButton>, Has<ChatMenuDisplay>, Has<MinimapDisplay>),
+        (Or<(With<SaveButton>, With<ChatMenuDisplay>, With<MinimapDisplay>)>, Without<TouchDpad>, Without<TouchActions>),
+    >,
+) {
+    if !touch_state.enabled {
+        return;
+    }
+    let Ok(window) = window_query.get_single() else { return };
+    let portrait = window.width() < window.height();
+    if touch_state.portrait == Some(portrait) {
+        return;
+    }
+    touch_state.portrait = Some(portrait);
+
+    let actions_height = BUTTON_SIZE * 2.0 + BUTTON_GAP;
+
+    if let Ok(mut node) = actions_query.get_single_mut() {
+        node.left = Val::Px(MARGIN);
+        node.bottom = Val::Px(MARGIN);
+    }
+    let dpad_bottom = if portrait { MARGIN + actions_height + BUTTON_GAP * 2.0 } else { MARGIN };
+    if let Ok(mut node) = dpad_query.get_single_mut() {
+        node.right = Val::Px(MARGIN);
+        node.bottom = Val::Px(dpad_bottom);
+    }
+
+    // 左下のボタンの上
+    let save_bottom = MARGIN + actions_height + BUTTON_GAP * 2.0;
+    for (mut node, is_save, is_chat_menu, is_minimap) in &mut others_query {
+        if is_save {
+            node.bottom = Val::Px(save_bottom);
+        } else if is_chat_menu {
+            node.bottom = Val::Px(save_bottom + 50.0);
+        } else if is_minimap {
+            node.bottom = Val::Auto;
+            node.top = Val::Px(MARGIN);
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use bevy::prelude::*;
 use crate::components::*;
 use crate::resources::*;
-use crate::database;
+use crate::storage::Storage;
+use crate::controls::{Action, ActionState, Controls};
 use crate::components::SaveButton;
+use crate::stamina::StaminaConfig;
 
 pub fn update_ui(
     player_query: Query<&GridPosition, With<Player>>,
@@ -27,11 +29,28 @@ pub fn update_ui(
     }
 }
 
+// 【新規】スタミナバー（ダッシュできないほど減ったら色を変える）
+pub fn update_stamina_bar(
+    stamina_config: Res<StaminaConfig>,
+    player_query: Query<&Stamina, With<Player>>,
+    mut bar_query: Query<(&mut Node, &mut BackgroundColor), With<StaminaBar>>,
+) {
+    let Ok(stamina) = player_query.get_single() else { return };
+    let Ok((mut node, mut color)) = bar_query.get_single_mut() else { return };
+    let ratio = if stamina_config.max > 0.0 { stamina.0 / stamina_config.max } else { 0.0 };
+    let width = Val::Percent(ratio.clamp(0.0, 1.0) * 100.0);
+    if node.width != width {
+        node.width = width;
+    }
+    let bar_color = if stamina.0 >= stamina_config.step_cost { Color::srgb(0.2, 0.7, 0.3) } else { Color::srgb(0.8, 0.3, 0.2) };
+    color.set_if_neq(BackgroundColor(bar_color));
+}
+
 pub fn update_chat_ui(
     time: Res<Time>,
     mut chat_log: ResMut<ChatLog>,
     mut chat_text_query: Query<&mut Text, With<ChatDisplay>>,
-    mut emoji_query: Query<(&mut Text, &mut EmojiTimer), (With<PlayerEmoji>, Without<ChatDisplay>)>,
+    mut emoji_query: Query<(&mut Text2d, &mut EmojiTimer), With<PlayerEmoji>>,
     // 【新規】声のエフェクト用
     mut voice_query: Query<&mut Visibility, With<VoiceEffect>>,
 ) {
@@ -80,8 +99,11 @@ pub fn update_chat_ui(
     }
 }
 
+// 元の入れ子の if と format! はそのまま残す
+#[allow(clippy::collapsible_if, clippy::useless_format)]
 pub fn update_chat_menu_ui(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
+    controls: Res<Controls>,
     player_query: Query<&Vocabulary, With<Player>>,
     mut menu_query: Query<(&mut Text, &mut Node), With<ChatMenuDisplay>>,
     emoji_config: Res<EmojiConfig>,
@@ -89,7 +111,7 @@ pub fn update_chat_menu_ui(
 ) {
     let (mut text, mut node) = menu_query.single_mut();
     
-    if keyboard_input.pressed(KeyCode::Space) {
+    if actions.pressed(Action::OpenMenu) {
         node.display = Display::Flex;
         menu_state.is_open = true;
     } else {
@@ -101,12 +123,12 @@ pub fn update_chat_menu_ui(
     let Ok(vocab) = player_query.get_single() else { return };
     let total_count = vocab.words.len();
     
-    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+    if actions.just_pressed(Action::MoveUp) {
         if menu_state.selected_index > 0 {
             menu_state.selected_index -= 1;
         }
     }
-    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+    if actions.just_pressed(Action::MoveDown) {
         if menu_state.selected_index < total_count.saturating_sub(1) {
             menu_state.selected_index += 1;
         }
@@ -126,27 +148,36 @@ pub fn update_chat_menu_ui(
     for i in start..end {
         let word = &vocab.words[i];
         let prefix = if i < 4 {
-            format!("[{}]", i + 1)
+            format!("[{}]", controls.key_label(Action::Speak(i as u8)))
         } else {
             format!(" - ")
         };
-        menu_str.push_str(&format!("{} {}\n", prefix, word));
+        // 【新規】一番上が選んでいる言葉（Confirm で言う）
+        let marker = if i == start { "▶" } else { " " };
+        menu_str.push_str(&format!("{}{} {}\n", marker, prefix, word));
     }
 
     if end < total_count {
         menu_str.push_str("  (▼ down)\n");
     }
     
+    menu_str.push_str(&format!("[{}] Say ▶\n", controls.key_label(Action::Confirm)));
+
     menu_str.push_str("\nEmotes:\n");
-    menu_str.push_str("[A] 👍\n");
-    menu_str.push_str(&format!("[S] {}\n", emoji_config.s_key));
-    menu_str.push_str(&format!("[D] {}\n", emoji_config.d_key));
+    menu_str.push_str(&format!("[{}] 👍\n", controls.key_label(Action::EmoteThumb)));
+    menu_str.push_str(&format!("[{}] {}\n", controls.key_label(Action::EmoteS), emoji_config.s_key));
+    menu_str.push_str(&format!("[{}] {}\n", controls.key_label(Action::EmoteD), emoji_config.d_key));
 
     text.0 = menu_str;
 }
 
+// 添字で回すループは元の書き方のまま
+#[allow(clippy::needless_range_loop)]
 pub fn update_emoji_select_menu(
     emoji_state: Res<EmojiSelectState>,
+    emoji_catalog: Res<EmojiCatalog>,
+    emoji_config: Res<EmojiConfig>,
+    controls: Res<Controls>,
     mut query: Query<(&mut Text, &mut Node), With<EmojiSelectMenuDisplay>>,
 ) {
     let (mut text, mut node) = query.single_mut();
@@ -157,21 +188,22 @@ pub fn update_emoji_select_menu(
     }
     node.display = Display::Flex;
 
+    // 【変更】割り当てたキーの名前を出す
     let target_str = match emoji_state.target_key {
-        Some(KeyCode::KeyS) => "S Key",
-        Some(KeyCode::KeyD) => "D Key",
-        _ => "Unknown",
+        Some(action) => format!("{} Key", controls.key_label(action)),
+        None => "Unknown".to_string(),
     };
 
     let mut content = format!("Select Emoji for [{}]:\n\n", target_str);
     
     let visible_count = 7;
-    let total_count = EMOJI_LIST.len();
+    let choices = emoji_catalog.choices(&emoji_config.unlocked);
+    let total_count = choices.len();
     
     let start_index = if emoji_state.selected_index < visible_count / 2 {
         0
-    } else if emoji_state.selected_index > total_count - visible_count / 2 {
-        if total_count > visible_count { total_count - visible_count } else { 0 }
+    } else if emoji_state.selected_index > total_count.saturating_sub(visible_count / 2) {
+        total_count.saturating_sub(visible_count)
     } else {
         emoji_state.selected_index - visible_count / 2
     };
@@ -181,14 +213,14 @@ pub fn update_emoji_select_menu(
     if start_index > 0 { content.push_str("  ... (more) ...\n"); }
 
     for i in start_index..end_index {
-        let emoji = EMOJI_LIST[i];
+        let emoji = choices[i];
         let cursor = if i == emoji_state.selected_index { ">" } else { " " };
         content.push_str(&format!("{} {}\n", cursor, emoji));
     }
 
     if end_index < total_count { content.push_str("  ... (more) ...\n"); }
     
-    content.push_str("\n[Enter] Select");
+    content.push_str(&format!("\n[{}] Select", controls.key_label(Action::Confirm)));
     text.0 = content;
 }
 
@@ -217,30 +249,40 @@ pub fn update_notification_ui(
 pub fn handle_save_button_interaction(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<SaveButton>)>,
     current_user: Res<CurrentUser>,
-    player_query: Query<(&GridPosition, &Vocabulary), With<Player>>,
+    mut player_query: Query<(&mut GridPosition, &Vocabulary, &Inventory), With<Player>>,
     emoji_config: Res<EmojiConfig>,
-    db_pool: Res<DbPool>,
+    storage: Res<Storage>,
     mut notification: ResMut<NotificationState>,
+    mut move_path: ResMut<MovePath>,
 ) {
     for interaction in &interaction_query {
         if *interaction == Interaction::Pressed {
             if current_user.username.is_empty() { return; }
 
-            if let Ok((pos, vocab)) = player_query.get_single() {
-                match database::save_user_data(
-                    &db_pool.0,
+            if let Ok((mut pos, vocab, inventory)) = player_query.get_single_mut() {
+                match storage.save_user(
                     &current_user.username,
                     pos.x,
                     pos.y,
                     vocab.words.clone(),
                     &emoji_config.s_key,
                     &emoji_config.d_key,
+                    inventory.items.clone(),
                 ) {
-                    Ok(_) => {
+                    Ok(None) => {
                         notification.message = "Game Saved!".to_string();
                         notification.is_visible = true;
                         notification.timer.reset();
                     },
+                    // 【新規】位置が認められなかった: 保存されている位置に戻す
+                    Ok(Some((x, y))) => {
+                        pos.x = x;
+                        pos.y = y;
+                        move_path.0.clear();
+                        notification.message = "Saved, but your position was rejected.\nMoved back to the last saved spot.".to_string();
+                        notification.is_visible = true;
+                        notification.timer.reset();
+                    },
                     Err(e) => {
                         notification.message = format!("Save Error: {}", e);
                         notification.is_visible = true;
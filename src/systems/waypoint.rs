@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use crate::components::*;
+use crate::resources::*;
+use crate::constants::{MAX_WAYPOINTS, MAX_WAYPOINT_NAME_LEN};
+use crate::map::is_teleport_stone;
+use crate::storage::Storage;
+use crate::controls::{Action, ActionState, Controls};
+use crate::systems::minimap::MapState;
+
+// ==========================================
+// 【新規】ウェイポイントとテレポート石
+// ワールドマップを開いている間、左上にウェイポイントの一覧を出す。
+// P で今いる場所に名前を付けて保存、Backspace (Action::Remove) で削除、
+// テレポート石の上で Enter を押すと選んだウェイポイントへ飛ぶ（位置は GridPosition を書き換えるだけ）。
+// サーバー接続中はサーバーが石の上か確かめ、行き先を Correction で返す
+// ==========================================
+
+#[derive(Resource, Default)]
+pub struct WaypointMenu {
+    // 名前を入力している間は Some（文字を受け付け、他の操作は止める）
+    naming: Option<String>,
+    message: String,
+}
+
+#[derive(Component)]
+pub struct WaypointListDisplay;
+
+// ログイン時に読んだウェイポイントを Waypoints に移し、一覧の表示を作る
+pub fn setup_waypoints(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut current_user: ResMut<CurrentUser>,
+    mut waypoints: ResMut<Waypoints>,
+    mut menu: ResMut<WaypointMenu>,
+) {
+    waypoints.points = std::mem::take(&mut current_user.waypoints);
+    *menu = WaypointMenu::default();
+
+    let jp_font = asset_server.load("fonts/NotoSansJP-Bold.ttf");
+    commands.spawn((
+        Text::new(""),
+        TextFont { font: jp_font, font_size: 16.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            left: Val::Px(20.0),
+            display: Display::None,
+            padding: UiRect::all(Val::Px(12.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        WaypointListDisplay,
+        BackgroundColor(Color::srgba(0.3, 0.0, 0.3, 0.9)),
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(10.0)),
+        ZIndex(110),
+        GameEntity,
+    ));
+}
+
+// 1 文字のキー入力（と空白）だけを取り出す
+fn typed_char(key: &Key) -> Option<char> {
+    match key {
+        Key::Character(s) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
+            }
+        }
+        Key::Space => Some(' '),
+        _ => None,
+    }
+}
+
+// ワールドマップを開いている間の一覧の操作（PreUpdate、ワールドマップの開閉より前）
+pub fn handle_waypoint_input(
+    // 名前の入力（文字と、1 文字消す Backspace）はキーの入力のまま読む
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut actions: ResMut<ActionState>,
+    mut menu: ResMut<WaypointMenu>,
+    mut map_state: ResMut<MapState>,
+    mut waypoints: ResMut<Waypoints>,
+    storage: Res<Storage>,
+    current_user: Res<CurrentUser>,
+    mut notification: ResMut<NotificationState>,
+    mut move_path: ResMut<MovePath>,
+    mut player_query: Query<&mut GridPosition, With<Player>>,
+) {
+    // 入力を始めたフレームの P は名前に入れないように、文字は先に読んでおく
+    let typed: Vec<Key> = keyboard_events.read().filter(|e| e.state.is_pressed()).map(|e| e.logical_key.clone()).collect();
+
+    if !map_state.world_open {
+        menu.naming = None;
+        if map_state.selected_waypoint.is_some() {
+            map_state.selected_waypoint = None;
+        }
+        return;
+    }
+    let count = waypoints.points.len();
+    let selected = if count == 0 { None } else { Some(map_state.selected_waypoint.unwrap_or(0).min(count - 1)) };
+    if map_state.selected_waypoint != selected {
+        map_state.selected_waypoint = selected;
+    }
+    let Ok(mut pos) = player_query.get_single_mut() else { return };
+
+    // 名前の入力中
+    if let Some(mut name) = menu.naming.take() {
+        for key in &typed {
+            if *key == Key::Backspace {
+                name.pop();
+            } else if let Some(c) = typed_char(key) {
+                if (c.is_alphanumeric() || c == ' ' || c == '-') && name.chars().count() < MAX_WAYPOINT_NAME_LEN {
+                    name.push(c);
+                }
+            }
+        }
+        if actions.just_pressed(Action::Cancel) {
+            menu.message.clear();
+        } else if actions.just_pressed(Action::Confirm) {
+            let name = name.trim().to_string();
+            let exists = waypoints.points.iter().any(|w| w.name == name);
+            menu.message = if !Waypoint::is_valid_name(&name) {
+                format!("Name: 1-{} letters, digits, spaces or -", MAX_WAYPOINT_NAME_LEN)
+            } else if !exists && count >= MAX_WAYPOINTS {
+                format!("Waypoint limit reached ({})", MAX_WAYPOINTS)
+            } else {
+                match storage.add_waypoint(&current_user.username, &name, pos.x, pos.y) {
+                    Ok(()) => {
+                        let waypoint = Waypoint { name: name.clone(), x: pos.x, y: pos.y };
+                        match waypoints.points.iter().position(|w| w.name == name) {
+                            Some(i) => {
+                                waypoints.points[i] = waypoint;
+                                map_state.selected_waypoint = Some(i);
+                            }
+                            None => {
+                                waypoints.points.push(waypoint);
+                                map_state.selected_waypoint = Some(waypoints.points.len() - 1);
+                            }
+                        }
+                        format!("Saved {} at ({}, {})", name, pos.x, pos.y)
+                    }
+                    Err(e) => e,
+                }
+            };
+        } else {
+            menu.naming = Some(name);
+        }
+        actions.consume();
+        return;
+    }
+
+    if actions.just_pressed(Action::MoveUp) {
+        if let Some(i) = selected {
+            map_state.selected_waypoint = Some(i.checked_sub(1).unwrap_or(count - 1));
+        }
+    } else if actions.just_pressed(Action::MoveDown) {
+        if let Some(i) = selected {
+            map_state.selected_waypoint = Some((i + 1) % count);
+        }
+    } else if actions.just_pressed(Action::AddWaypoint) {
+        menu.naming = Some(String::new());
+        menu.message.clear();
+    } else if actions.just_pressed(Action::Remove) {
+        let Some(i) = selected else { return };
+        let name = waypoints.points[i].name.clone();
+        menu.message = match storage.delete_waypoint(&current_user.username, &name) {
+            Ok(()) => {
+                waypoints.points.remove(i);
+                format!("Removed {}", name)
+            }
+            Err(e) => e,
+        };
+    } else if actions.just_pressed(Action::Confirm) {
+        let Some(i) = selected else { return };
+        let name = waypoints.points[i].name.clone();
+        if !is_teleport_stone(pos.x, pos.y) {
+            menu.message = "Stand on a teleport stone to travel".to_string();
+            return;
+        }
+        match storage.teleport(&current_user.username, &name, (pos.x, pos.y)) {
+            // DB 直接: その場で動かす
+            Ok(Some((x, y))) => {
+                pos.x = x;
+                pos.y = y;
+                notification.message = format!("Teleported to {}", name);
+            }
+            // サーバー接続中: 行き先は Correction で届く
+            Ok(None) => notification.message = format!("Teleporting to {}...", name),
+            Err(e) => {
+                menu.message = e;
+                return;
+            }
+        }
+        notification.is_visible = true;
+        notification.timer.reset();
+        move_path.0.clear();
+        menu.message.clear();
+        map_state.world_open = false;
+        // 閉じたのでワールドマップ側では止めない。Enter が他で使われないようにここで止める
+        actions.consume();
+    }
+}
+
+// テレポート石に乗ったら知らせる
+pub fn notify_teleport_stone(
+    controls: Res<Controls>,
+    mut notification: ResMut<NotificationState>,
+    mut last_cell: Local<Option<(i64, i64)>>,
+    player_query: Query<&GridPosition, (With<Player>, Changed<GridPosition>)>,
+) {
+    let Ok(pos) = player_query.get_single() else { return };
+    let cell = (pos.x, pos.y);
+    if *last_cell == Some(cell) {
+        return;
+    }
+    *last_cell = Some(cell);
+    if is_teleport_stone(pos.x, pos.y) {
+        notification.message = format!("Teleport stone! Open the map [{}] to travel", controls.key_label(Action::WorldMap));
+        notification.is_visible = true;
+        notification.timer.reset();
+    }
+}
+
+pub fn update_waypoint_ui(
+    menu: Res<WaypointMenu>,
+    map_state: Res<MapState>,
+    waypoints: Res<Waypoints>,
+    controls: Res<Controls>,
+    player_query: Query<&GridPosition, With<Player>>,
+    mut query: Query<(&mut Text, &mut Node), With<WaypointListDisplay>>,
+) {
+    let Ok((mut text, mut node)) = query.get_single_mut() else { return };
+    if !map_state.world_open {
+        node.display = Display::None;
+        return;
+    }
+    node.display = Display::Flex;
+
+    let mut content = format!("Waypoints ({}/{})\n", waypoints.points.len(), MAX_WAYPOINTS);
+    if waypoints.points.is_empty() {
+        content.push_str("  (none yet)\n");
+    }
+    for (i, waypoint) in waypoints.points.iter().enumerate() {
+        let marker = if map_state.selected_waypoint == Some(i) { "▶" } else { " " };
+        content.push_str(&format!("{} {}  ({}, {})\n", marker, waypoint.name, waypoint.x, waypoint.y));
+    }
+
+    if let Some(name) = &menu.naming {
+        content.push_str(&format!("\nName: {}_\n[{}] Save  [{}] Cancel", name, controls.key_label(Action::Confirm), controls.key_label(Action::Cancel)));
+    } else {
+        let on_stone = player_query.get_single().is_ok_and(|pos| is_teleport_stone(pos.x, pos.y));
+        content.push_str(&format!(
+            "\n[{}] Add here  [{}] Remove\n[{}] Travel{}",
+            controls.key_label(Action::AddWaypoint),
+            controls.key_label(Action::Remove),
+            controls.key_label(Action::Confirm),
+            if on_stone { "" } else { " (stand on a teleport stone)" },
+        ));
+    }
+    if !menu.message.is_empty() {
+        content.push_str(&format!("\n\n{}", menu.message));
+    }
+    if text.0 != content {
+        text.0 = content;
+    }
+}
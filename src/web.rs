@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+use tiny_http::{Header, Response, Server};
+
+// ==========================================
+// 【新規】ブラウザ版の配信
+// evol-server の WEB_ROOT (wasm-bindgen の出力と assets/) を HTTP で返すだけ。
+// ゲームの通信は WebSocket (SERVER_BIND) なので、ここは静的ファイルのみ
+// ==========================================
+
+// 配信のアドレス (環境変数 WEB_BIND で変更可)
+pub const DEFAULT_WEB_BIND: &str = "0.0.0.0:8080";
+
+// 別スレッドで配信を始める
+pub fn serve_static(bind: &str, root: PathBuf) -> Result<(), String> {
+    let server = Server::http(bind).map_err(|e| e.to_string())?;
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = match resolve(&root, request.url()).and_then(|path| fs::read(&path).ok().map(|body| (path, body))) {
+                Some((path, body)) => {
+                    let content_type = Header::from_bytes("Content-Type", content_type(&path)).unwrap();
+                    Response::from_data(body).with_header(content_type)
+                }
+                None => Response::from_string("Not Found").with_status_code(404),
+            };
+            let _ = request.respond(response);
+        }
+    });
+
+    Ok(())
+}
+
+// URL をファイルに変換する（root の外は返さない）
+fn resolve(root: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    // 先頭の / は 1 つだけ外す（"//etc" は絶対パスとして断る）。
+    // % は復号しないので、%2e%2e や %2f のような符号化された区切りも断る
+    let path = path.strip_prefix('/')?;
+    if path.contains(['%', '\\']) {
+        return None;
+    }
+    let mut file = root.to_path_buf();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => file.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    Some(file)
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript",
+        // ブラウザが wasm をストリーミングでコンパイルするのに必要
+        Some("wasm") => "application/wasm",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("ttf") => "font/ttf",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // root の中のファイルだけを返す
+    #[test]
+    fn resolve_stays_inside_root() {
+        let root = Path::new("/srv/web");
+        assert_eq!(resolve(root, "/evolution_game.js?v=2"), Some(root.join("evolution_game.js")));
+        assert_eq!(resolve(root, "/assets/./fonts/a.ttf"), Some(root.join("assets/fonts/a.ttf")));
+        let dir = std::env::temp_dir();
+        assert_eq!(resolve(&dir, "/"), Some(dir.join("index.html")));
+    }
+
+    // .. や絶対パス、符号化された区切りは返さない
+    #[test]
+    fn resolve_rejects_escapes() {
+        let root = Path::new("/srv/web");
+        for url in [
+            "/../etc/passwd",
+            "/assets/../../etc/passwd",
+            "//etc/passwd",
+            "etc/passwd",
+            "/%2e%2e/etc/passwd",
+            "/assets%2f..%2f..%2fetc/passwd",
+            "/..%5c..%5cetc/passwd",
+            "/..\\etc\\passwd",
+        ] {
+            assert_eq!(resolve(root, url), None, "{}", url);
+        }
+    }
+}